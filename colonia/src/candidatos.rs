@@ -0,0 +1,91 @@
+use crate::Peso;
+
+// Listas de candidatos y conjunto de visitados, compartidos por las
+// construcciones de ambas colonias. Los nodos son indices u32 (ids desde 0 en
+// `sistema_hormiga`, desde 1 en `rutas_vehiculares`).
+
+// Para cada nodo, los vecinos que la hormiga revisa primero
+pub type ListasCandidatos = Vec<Vec<u32>>;
+
+// Ordena la vecindad de cada nodo i por `distancia(i, j)` y se queda con los
+// k mas cercanos; con k = 0 o k mayor a la vecindad la lista queda completa.
+// El orden es estable, asi que los empates conservan el orden de entrada.
+pub fn k_mas_cercanos(
+    vecindades: Vec<Vec<u32>>,
+    distancia: impl Fn(usize, u32) -> Peso,
+    k: usize,
+) -> ListasCandidatos {
+    vecindades
+        .into_iter()
+        .enumerate()
+        .map(|(i, mut vecinos)| {
+            vecinos.sort_by(|&a, &b| distancia(i, a).total_cmp(&distancia(i, b)));
+            if k > 0 {
+                vecinos.truncate(k);
+            }
+            vecinos
+        })
+        .collect()
+}
+
+// Conjunto de nodos visitados como mapa de bits: insertar y consultar en O(1)
+// en lugar de recorrer el camino con `contains`. Admite los nodos 0..n.
+#[derive(Debug, Clone)]
+pub struct Visitados {
+    bloques: Vec<u64>,
+}
+
+impl Visitados {
+    pub fn new(n: usize) -> Visitados {
+        Visitados {
+            bloques: vec![0; n.div_ceil(64)],
+        }
+    }
+
+    pub fn insertar(&mut self, nodo: u32) {
+        self.bloques[nodo as usize / 64] |= 1 << (nodo % 64);
+    }
+
+    pub fn contiene(&self, nodo: u32) -> bool {
+        self.bloques[nodo as usize / 64] & (1 << (nodo % 64)) != 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn candidatos_ordenados_y_recortados() {
+        let distancias = [[0.0, 3.0, 1.0, 2.0], [5.0, 0.0, 5.0, 4.0]];
+        let vecindades = vec![vec![1, 2, 3], vec![0, 2, 3]];
+        let distancia = |i: usize, j: u32| distancias[i][j as usize];
+
+        assert_eq!(
+            k_mas_cercanos(vecindades.clone(), distancia, 2),
+            vec![vec![2, 3], vec![3, 0]]
+        );
+        // Los empates (0 y 2 a distancia 5 de 1) conservan su orden
+        assert_eq!(
+            k_mas_cercanos(vecindades.clone(), distancia, 0),
+            vec![vec![2, 3, 1], vec![3, 0, 2]]
+        );
+        assert_eq!(
+            k_mas_cercanos(vecindades, distancia, 10),
+            vec![vec![2, 3, 1], vec![3, 0, 2]]
+        );
+    }
+
+    #[test]
+    fn visitados_en_varios_bloques() {
+        let mut visitados = Visitados::new(130);
+        for nodo in [0, 63, 64, 129] {
+            assert!(!visitados.contiene(nodo));
+            visitados.insertar(nodo);
+            assert!(visitados.contiene(nodo));
+        }
+        assert!(!visitados.contiene(1));
+        assert!(!visitados.contiene(65));
+        assert!(!visitados.contiene(128));
+    }
+}
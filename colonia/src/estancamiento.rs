@@ -0,0 +1,221 @@
+use crate::{Peso, Rho};
+
+// Diagnosticos de convergencia de los rastros de feromona y accion a tomar
+// cuando la colonia se estanca.
+
+// λ del factor de ramificacion (Gambardella y Dorigo): una arista de i
+// cuenta si τ_ij >= τ_min(i) + λ (τ_max(i) - τ_min(i))
+const LAMBDA_RAMIFICACION: Peso = 0.05;
+
+// Al estancarse con la accion `AumentarEvaporacion`, ρ se multiplica por
+// este factor sin pasar de RHO_MAXIMO; vuelve al valor original al mejorar
+pub const FACTOR_EVAPORACION: Rho = 1.5;
+pub const RHO_MAXIMO: Rho = 0.9;
+
+// Iteraciones minimas entre dos acciones, para que los rastros reaccionen
+// antes de volver a evaluar el estancamiento
+pub const ITERACIONES_ENTRE_ACCIONES: usize = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AccionEstancamiento {
+    // Solo se reportan los diagnosticos
+    Ninguna,
+    // Los rastros vuelven a su valor inicial
+    Reiniciar,
+    // Termina la ejecucion antes de max_iteraciones
+    Detener,
+    // Se aumenta ρ: los rastros viejos se olvidan mas rapido. Con los
+    // limites de MMAS las aristas abandonadas bajan antes a τmin; en AS sin
+    // limites tambien acelera la convergencia hacia los depositos recientes
+    AumentarEvaporacion,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Diagnostico {
+    // Promedio sobre los nodos del numero de aristas con feromona alta;
+    // tiende a 1 (ATSP) o 2 (TSP simetrico) cuando la colonia converge
+    pub ramificacion: Peso,
+    // Entropia promedio de τ_ij / Σ_k τ_ik en cada nodo, normalizada por
+    // ln(grado): 1 con rastros uniformes y cerca de 0 al converger
+    pub entropia: Peso,
+    pub iteraciones_sin_mejora: usize,
+}
+
+impl Diagnostico {
+    // Texto que acompaña a la linea de progreso
+    pub fn resumen(&self) -> String {
+        format!(
+            "λ-ramificacion: {:.2}, entropia: {:.3}, sin mejora: {}",
+            self.ramificacion, self.entropia, self.iteraciones_sin_mejora
+        )
+    }
+}
+
+// Criterios de estancamiento; un umbral en 0 desactiva su criterio
+#[derive(Debug, Clone, Copy)]
+pub struct CriterioEstancamiento {
+    pub accion: AccionEstancamiento,
+    pub iteraciones_sin_mejora: usize,
+    pub umbral_ramificacion: Peso,
+    pub umbral_entropia: Peso,
+}
+
+impl Default for CriterioEstancamiento {
+    fn default() -> CriterioEstancamiento {
+        CriterioEstancamiento {
+            accion: AccionEstancamiento::Ninguna,
+            iteraciones_sin_mejora: 50,
+            umbral_ramificacion: 0.0,
+            umbral_entropia: 0.0,
+        }
+    }
+}
+
+impl CriterioEstancamiento {
+    // `iteraciones_desde_accion` cuenta desde la ultima accion tomada (o
+    // desde el inicio), de modo que una accion no se repite enseguida
+    pub fn estancado(&self, diagnostico: &Diagnostico, iteraciones_desde_accion: usize) -> bool {
+        if self.accion == AccionEstancamiento::Ninguna
+            || iteraciones_desde_accion < ITERACIONES_ENTRE_ACCIONES
+        {
+            return false;
+        }
+        let sin_mejora = diagnostico
+            .iteraciones_sin_mejora
+            .min(iteraciones_desde_accion);
+        (self.iteraciones_sin_mejora > 0 && sin_mejora >= self.iteraciones_sin_mejora)
+            || (self.umbral_ramificacion > 0.0
+                && diagnostico.ramificacion <= self.umbral_ramificacion)
+            || (self.umbral_entropia > 0.0 && diagnostico.entropia <= self.umbral_entropia)
+    }
+}
+
+// `rastros_por_nodo` da, para cada nodo, la feromona de sus aristas
+// salientes; los nodos sin aristas no cuentan en los promedios
+pub fn diagnosticar_rastros<I>(rastros_por_nodo: I, iteraciones_sin_mejora: usize) -> Diagnostico
+where
+    I: IntoIterator<Item = Vec<Peso>>,
+{
+    let mut ramificacion = 0.0;
+    let mut entropia = 0.0;
+    let mut nodos = 0;
+
+    for rastros in rastros_por_nodo {
+        if rastros.is_empty() {
+            continue;
+        }
+        let minimo = rastros.iter().copied().fold(Peso::INFINITY, Peso::min);
+        let maximo = rastros.iter().copied().fold(0.0, Peso::max);
+        let corte = minimo + LAMBDA_RAMIFICACION * (maximo - minimo);
+        ramificacion += rastros.iter().filter(|&&tau| tau >= corte).count() as Peso;
+
+        let total: Peso = rastros.iter().sum();
+        if rastros.len() > 1 && total > 0.0 {
+            let h: Peso = rastros
+                .iter()
+                .map(|&tau| tau / total)
+                .filter(|&p| p > 0.0)
+                .map(|p| -p * p.ln())
+                .sum();
+            entropia += h / (rastros.len() as Peso).ln();
+        } else {
+            entropia += 1.0;
+        }
+        nodos += 1;
+    }
+
+    let nodos = nodos.max(1) as Peso;
+    Diagnostico {
+        ramificacion: ramificacion / nodos,
+        entropia: entropia / nodos,
+        iteraciones_sin_mejora,
+    }
+}
+
+pub fn aumentar_evaporacion(rho: Rho) -> Rho {
+    (rho * FACTOR_EVAPORACION).min(RHO_MAXIMO).max(rho)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn diagnostico(rastros: Vec<Vec<Peso>>, sin_mejora: usize) -> Diagnostico {
+        diagnosticar_rastros(rastros, sin_mejora)
+    }
+
+    #[test]
+    fn rastros_uniformes() {
+        let d = diagnostico(vec![vec![0.5; 4]; 5], 0);
+        assert!((d.entropia - 1.0).abs() < 1e-12);
+        assert_eq!(d.ramificacion, 4.0);
+    }
+
+    #[test]
+    fn rastros_convergidos() {
+        // Una arista dominante por nodo
+        let rastros = (0..5)
+            .map(|i| {
+                (0..4)
+                    .map(|j| if i % 4 == j { 1.0 } else { 1e-6 })
+                    .collect()
+            })
+            .collect();
+        let d = diagnostico(rastros, 3);
+        assert_eq!(d.ramificacion, 1.0);
+        assert!(d.entropia < 0.01);
+        assert_eq!(d.iteraciones_sin_mejora, 3);
+    }
+
+    #[test]
+    fn nodos_sin_aristas_no_cuentan() {
+        let d = diagnostico(vec![vec![], vec![1.0, 1.0], vec![]], 0);
+        assert_eq!(d.ramificacion, 2.0);
+        assert!((d.entropia - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn criterios_de_estancamiento() {
+        let criterio = CriterioEstancamiento {
+            accion: AccionEstancamiento::Reiniciar,
+            iteraciones_sin_mejora: 20,
+            umbral_ramificacion: 1.5,
+            umbral_entropia: 0.0,
+        };
+        let activo = Diagnostico {
+            ramificacion: 3.0,
+            entropia: 0.9,
+            iteraciones_sin_mejora: 5,
+        };
+        assert!(!criterio.estancado(&activo, 50));
+
+        let sin_mejora = Diagnostico {
+            iteraciones_sin_mejora: 25,
+            ..activo
+        };
+        assert!(criterio.estancado(&sin_mejora, 25));
+        // Las iteraciones sin mejora se cuentan desde la ultima accion
+        assert!(!criterio.estancado(&sin_mejora, 15));
+
+        let ramificacion_baja = Diagnostico {
+            ramificacion: 1.2,
+            ..activo
+        };
+        assert!(criterio.estancado(&ramificacion_baja, ITERACIONES_ENTRE_ACCIONES));
+        assert!(!criterio.estancado(&ramificacion_baja, ITERACIONES_ENTRE_ACCIONES - 1));
+
+        let ninguna = CriterioEstancamiento {
+            accion: AccionEstancamiento::Ninguna,
+            ..criterio
+        };
+        assert!(!ninguna.estancado(&ramificacion_baja, 100));
+    }
+
+    #[test]
+    fn evaporacion_acotada() {
+        assert!((aumentar_evaporacion(0.1) - 0.15).abs() < 1e-12);
+        assert_eq!(aumentar_evaporacion(0.8), RHO_MAXIMO);
+        // Un ρ ya mayor que el maximo no se reduce
+        assert_eq!(aumentar_evaporacion(0.95), 0.95);
+    }
+}
@@ -0,0 +1,13 @@
+// Piezas de la colonia compartidas por `sistema_hormiga` y
+// `rutas_vehiculares` que no dependen de como guarda cada una sus rastros.
+
+pub mod candidatos;
+pub mod estancamiento;
+pub mod limites;
+
+pub use candidatos::*;
+pub use estancamiento::*;
+pub use limites::*;
+
+pub type Peso = f64;
+pub type Rho = f64;
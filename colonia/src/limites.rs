@@ -0,0 +1,82 @@
+use crate::{Peso, Rho};
+
+// Limites de feromona de MAX-MIN Ant System (Stützle y Hoos) derivados del
+// mejor costo conocido
+#[derive(Debug, Clone, Copy)]
+pub struct LimitesFeromona {
+    pub minimo: Peso,
+    pub maximo: Peso,
+}
+
+impl LimitesFeromona {
+    // τmax = 1 / (ρ * L_mejor)
+    // τmin = τmax * (1 - p_mejor^(1/n)) / ((n/2 - 1) * p_mejor^(1/n))
+    // con n/2 - 1 (el numero promedio de opciones por paso) de al menos 1 y
+    // τmin nunca por encima de τmax
+    pub fn new(mejor_costo: u32, p: Rho, n: usize, p_mejor: f64) -> LimitesFeromona {
+        let maximo = 1.0 / (p * mejor_costo.max(1) as Peso);
+        let raiz = p_mejor.powf(1.0 / n.max(1) as f64);
+        let promedio = (n as f64 / 2.0 - 1.0).max(1.0);
+        let minimo = (maximo * (1.0 - raiz) / (promedio * raiz)).min(maximo);
+        LimitesFeromona { minimo, maximo }
+    }
+
+    // Solo el piso τmin, para Ant System
+    pub fn sin_maximo(self) -> LimitesFeromona {
+        LimitesFeromona {
+            minimo: self.minimo,
+            maximo: Peso::INFINITY,
+        }
+    }
+
+    // τ llevada a [τmin, τmax]
+    pub fn acotar(&self, feromona: Peso) -> Peso {
+        feromona.clamp(self.minimo, self.maximo)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cerca(a: f64, b: f64) -> bool {
+        (a - b).abs() <= 1e-12 * b.abs().max(1.0)
+    }
+
+    #[test]
+    fn formulas_de_mmas() {
+        let (costo, rho, n, p_mejor) = (1000, 0.02, 50, 0.05);
+        let limites = LimitesFeromona::new(costo, rho, n, p_mejor);
+
+        let maximo = 1.0 / (rho * costo as f64);
+        let raiz = p_mejor.powf(1.0 / n as f64);
+        let minimo = maximo * (1.0 - raiz) / ((n as f64 / 2.0 - 1.0) * raiz);
+        assert!(cerca(limites.maximo, 0.05));
+        assert!(cerca(limites.maximo, maximo));
+        assert!(cerca(limites.minimo, minimo));
+        assert!(limites.minimo > 0.0 && limites.minimo < limites.maximo);
+    }
+
+    #[test]
+    fn pocos_nodos_y_costo_cero() {
+        // Con n <= 4 el denominador n/2 - 1 se toma como 1, y τmin <= τmax
+        let limites = LimitesFeromona::new(0, 0.5, 3, 0.05);
+        assert!(cerca(limites.maximo, 2.0));
+        let raiz = 0.05f64.powf(1.0 / 3.0);
+        assert!(cerca(limites.minimo, (2.0 * (1.0 - raiz) / raiz).min(2.0)));
+        assert!(limites.minimo <= limites.maximo);
+    }
+
+    #[test]
+    fn acotar_a_los_limites() {
+        let limites = LimitesFeromona::new(200, 0.1, 30, 0.05);
+        assert_eq!(limites.acotar(10.0), limites.maximo);
+        assert_eq!(limites.acotar(0.0), limites.minimo);
+        let intermedio = (limites.minimo + limites.maximo) / 2.0;
+        assert_eq!(limites.acotar(intermedio), intermedio);
+
+        let sin_maximo = limites.sin_maximo();
+        assert_eq!(sin_maximo.acotar(10.0), 10.0);
+        assert_eq!(sin_maximo.acotar(0.0), limites.minimo);
+    }
+}
@@ -0,0 +1,390 @@
+use rand::prelude::*;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+
+pub type Coordenada = (u32, u32);
+
+// Peso de la diagonal en las matrices ATSP, como en las instancias ftv
+const DIAGONAL_ATSP: u32 = 100_000_000;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TipoInstancia {
+    // Matriz completa asimetrica (EXPLICIT / FULL_MATRIX)
+    Atsp,
+    // Coordenadas con distancia EUC_2D
+    Tsp,
+    // Coordenadas, demandas y capacidad en formato CVRPLIB
+    Cvrp,
+}
+
+// Distribucion de las demandas de los clientes (CVRP)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Demanda {
+    // Todas las demandas valen 1
+    Unitaria,
+    // Uniforme en [min, max]
+    Uniforme(u32, u32),
+    // Muchas pequeñas y pocas grandes: 80 % en [1, 10] y 20 % en [50, 100]
+    Sesgada,
+}
+
+// Posicion del deposito (CVRP)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Deposito {
+    Aleatorio,
+    Centro,
+    Esquina,
+}
+
+#[derive(Debug, Clone)]
+pub struct Parametros {
+    pub tipo: TipoInstancia,
+    // Numero de nodos, incluido el deposito en CVRP
+    pub n: usize,
+    pub semilla: u64,
+    // Las coordenadas son enteros en [0, lado] x [0, lado]
+    pub lado: u32,
+    // Numero de grupos de ciudades (0 = distribucion uniforme)
+    pub grupos: usize,
+    // Desviacion de cada grupo como fraccion del lado
+    pub dispersion: f64,
+    // Factor a en d_ij = e_ij * (1 + a * u_ij) con u_ij uniforme en [0, 1)
+    // por cada sentido; 0 da una matriz simetrica (ATSP)
+    pub asimetria: f64,
+    pub demanda: Demanda,
+    // Fraccion de la capacidad de la flota que ocupa la demanda total: cerca
+    // de 1 la capacidad es ajustada (CVRP)
+    pub ocupacion: f64,
+    // Vehiculos de la flota; None = uno por cada 6 clientes
+    pub vehiculos: Option<usize>,
+    pub deposito: Deposito,
+}
+
+impl Parametros {
+    pub fn new(tipo: TipoInstancia, n: usize, semilla: u64) -> Parametros {
+        Parametros {
+            tipo,
+            n,
+            semilla,
+            // Mismo rango que el conjunto A de Augerat en CVRP
+            lado: if tipo == TipoInstancia::Cvrp {
+                100
+            } else {
+                1000
+            },
+            grupos: 0,
+            dispersion: 0.05,
+            asimetria: 0.2,
+            demanda: Demanda::Uniforme(1, 30),
+            ocupacion: 0.9,
+            vehiculos: None,
+            deposito: Deposito::Aleatorio,
+        }
+    }
+
+    pub fn validar(&self) -> Result<(), String> {
+        if self.n < 2 {
+            return Err("La instancia necesita al menos 2 nodos".into());
+        }
+        if self.lado == 0 {
+            return Err("lado debe ser > 0".into());
+        }
+        if self.dispersion < 0.0 {
+            return Err("dispersion debe ser >= 0".into());
+        }
+        if self.asimetria < 0.0 {
+            return Err("asimetria debe ser >= 0".into());
+        }
+        if !(self.ocupacion > 0.0 && self.ocupacion <= 1.0) {
+            return Err("ocupacion debe estar en (0, 1]".into());
+        }
+        if self.vehiculos == Some(0) {
+            return Err("vehiculos debe ser >= 1".into());
+        }
+        if let Demanda::Uniforme(min, max) = self.demanda {
+            if min == 0 || min > max {
+                return Err("La demanda debe cumplir 1 <= min <= max".into());
+            }
+        }
+        Ok(())
+    }
+}
+
+// Normal estandar por Box-Muller
+fn normal(rng: &mut StdRng) -> f64 {
+    let u1: f64 = 1.0 - rng.random::<f64>();
+    let u2: f64 = rng.random();
+    (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+}
+
+// Sin grupos, coordenadas uniformes. Con grupos, cada ciudad se coloca
+// alrededor de un centro elegido al azar con desviacion `dispersion * lado`.
+fn generar_coordenadas(parametros: &Parametros, n: usize, rng: &mut StdRng) -> Vec<Coordenada> {
+    let lado = parametros.lado;
+    if parametros.grupos == 0 {
+        return (0..n)
+            .map(|_| (rng.random_range(0..=lado), rng.random_range(0..=lado)))
+            .collect();
+    }
+
+    let centros: Vec<(f64, f64)> = (0..parametros.grupos)
+        .map(|_| {
+            (
+                rng.random_range(0.0..=lado as f64),
+                rng.random_range(0.0..=lado as f64),
+            )
+        })
+        .collect();
+    let sigma = parametros.dispersion * lado as f64;
+    let acotar = |x: f64| x.round().clamp(0.0, lado as f64) as u32;
+    (0..n)
+        .map(|_| {
+            let (cx, cy) = centros[rng.random_range(0..centros.len())];
+            (
+                acotar(cx + sigma * normal(rng)),
+                acotar(cy + sigma * normal(rng)),
+            )
+        })
+        .collect()
+}
+
+fn euclidiana(a: Coordenada, b: Coordenada) -> f64 {
+    let dx = a.0 as f64 - b.0 as f64;
+    let dy = a.1 as f64 - b.1 as f64;
+    (dx * dx + dy * dy).sqrt()
+}
+
+fn generar_matriz_asimetrica(
+    parametros: &Parametros,
+    coordenadas: &[Coordenada],
+    rng: &mut StdRng,
+) -> Vec<Vec<u32>> {
+    let n = coordenadas.len();
+    (0..n)
+        .map(|i| {
+            (0..n)
+                .map(|j| {
+                    if i == j {
+                        return DIAGONAL_ATSP;
+                    }
+                    let factor = 1.0 + parametros.asimetria * rng.random::<f64>();
+                    (euclidiana(coordenadas[i], coordenadas[j]) * factor).round() as u32
+                })
+                .collect()
+        })
+        .collect()
+}
+
+fn generar_demandas(parametros: &Parametros, clientes: usize, rng: &mut StdRng) -> Vec<u32> {
+    (0..clientes)
+        .map(|_| match parametros.demanda {
+            Demanda::Unitaria => 1,
+            Demanda::Uniforme(min, max) => rng.random_range(min..=max),
+            Demanda::Sesgada => {
+                if rng.random::<f64>() < 0.8 {
+                    rng.random_range(1..=10)
+                } else {
+                    rng.random_range(50..=100)
+                }
+            }
+        })
+        .collect()
+}
+
+fn nombre_desde_ruta(ruta: &str) -> String {
+    std::path::Path::new(ruta)
+        .file_stem()
+        .map_or(ruta.to_string(), |s| s.to_string_lossy().into_owned())
+}
+
+fn escribir_atsp(
+    ruta: &str,
+    parametros: &Parametros,
+    rng: &mut StdRng,
+) -> Result<(), std::io::Error> {
+    let coordenadas = generar_coordenadas(parametros, parametros.n, rng);
+    let matriz = generar_matriz_asimetrica(parametros, &coordenadas, rng);
+
+    let mut salida = BufWriter::new(File::create(ruta)?);
+    writeln!(salida, "NAME: {}", nombre_desde_ruta(ruta))?;
+    writeln!(salida, "TYPE: ATSP")?;
+    writeln!(
+        salida,
+        "COMMENT: Generada (semilla {}, grupos {}, asimetria {})",
+        parametros.semilla, parametros.grupos, parametros.asimetria
+    )?;
+    writeln!(salida, "DIMENSION: {}", parametros.n)?;
+    writeln!(salida, "EDGE_WEIGHT_TYPE: EXPLICIT")?;
+    writeln!(salida, "EDGE_WEIGHT_FORMAT: FULL_MATRIX")?;
+    writeln!(salida, "EDGE_WEIGHT_SECTION")?;
+    for fila in &matriz {
+        let fila: Vec<String> = fila.iter().map(|d| format!("{:>10}", d)).collect();
+        writeln!(salida, "{}", fila.join(" "))?;
+    }
+    writeln!(salida, "EOF")?;
+    salida.flush()
+}
+
+fn escribir_tsp(
+    ruta: &str,
+    parametros: &Parametros,
+    rng: &mut StdRng,
+) -> Result<(), std::io::Error> {
+    let coordenadas = generar_coordenadas(parametros, parametros.n, rng);
+
+    let mut salida = BufWriter::new(File::create(ruta)?);
+    writeln!(salida, "NAME: {}", nombre_desde_ruta(ruta))?;
+    writeln!(salida, "TYPE: TSP")?;
+    writeln!(
+        salida,
+        "COMMENT: Generada (semilla {}, grupos {})",
+        parametros.semilla, parametros.grupos
+    )?;
+    writeln!(salida, "DIMENSION: {}", parametros.n)?;
+    writeln!(salida, "EDGE_WEIGHT_TYPE: EUC_2D")?;
+    writeln!(salida, "NODE_COORD_SECTION")?;
+    for (i, (x, y)) in coordenadas.iter().enumerate() {
+        writeln!(salida, "{} {} {}", i + 1, x, y)?;
+    }
+    writeln!(salida, "EOF")?;
+    salida.flush()
+}
+
+// Capacidad Q tal que la demanda total ocupe la fraccion `ocupacion` de la
+// flota, sin ser menor a la mayor demanda
+fn capacidad_vehiculo(demandas: &[u32], vehiculos: usize, ocupacion: f64) -> u32 {
+    let total: u32 = demandas.iter().sum();
+    let maxima = demandas.iter().copied().max().unwrap_or(1);
+    let capacidad = (total as f64 / (ocupacion * vehiculos as f64)).ceil() as u32;
+    capacidad.max(maxima)
+}
+
+fn escribir_cvrp(
+    ruta: &str,
+    parametros: &Parametros,
+    rng: &mut StdRng,
+) -> Result<(), std::io::Error> {
+    let clientes = parametros.n - 1;
+    let mut coordenadas = generar_coordenadas(parametros, clientes, rng);
+    let lado = parametros.lado;
+    let deposito = match parametros.deposito {
+        Deposito::Aleatorio => (rng.random_range(0..=lado), rng.random_range(0..=lado)),
+        Deposito::Centro => (lado / 2, lado / 2),
+        Deposito::Esquina => (0, 0),
+    };
+    coordenadas.insert(0, deposito);
+
+    let demandas = generar_demandas(parametros, clientes, rng);
+    let vehiculos = parametros.vehiculos.unwrap_or(clientes.div_ceil(6).max(1));
+    let capacidad = capacidad_vehiculo(&demandas, vehiculos, parametros.ocupacion);
+
+    // Mismo formato (con espacio antes de ':') que el conjunto A de CVRPLIB
+    let mut salida = BufWriter::new(File::create(ruta)?);
+    writeln!(salida, "NAME : {}", nombre_desde_ruta(ruta))?;
+    writeln!(
+        salida,
+        "COMMENT : (Generada, semilla {}, No of trucks: {}, ocupacion {})",
+        parametros.semilla, vehiculos, parametros.ocupacion
+    )?;
+    writeln!(salida, "TYPE : CVRP")?;
+    writeln!(salida, "DIMENSION : {}", parametros.n)?;
+    writeln!(salida, "EDGE_WEIGHT_TYPE : EUC_2D")?;
+    writeln!(salida, "CAPACITY : {}", capacidad)?;
+    writeln!(salida, "NODE_COORD_SECTION")?;
+    for (i, (x, y)) in coordenadas.iter().enumerate() {
+        writeln!(salida, " {} {} {}", i + 1, x, y)?;
+    }
+    writeln!(salida, "DEMAND_SECTION")?;
+    writeln!(salida, "1 0")?;
+    for (i, demanda) in demandas.iter().enumerate() {
+        writeln!(salida, "{} {}", i + 2, demanda)?;
+    }
+    writeln!(salida, "DEPOT_SECTION")?;
+    writeln!(salida, " 1")?;
+    writeln!(salida, " -1")?;
+    writeln!(salida, "EOF")?;
+    salida.flush()
+}
+
+// Genera la instancia y la escribe en `ruta`. La misma semilla y los mismos
+// parametros dan siempre el mismo archivo.
+pub fn generar_instancia(ruta: &str, parametros: &Parametros) -> Result<(), String> {
+    parametros.validar()?;
+    let mut rng = StdRng::seed_from_u64(parametros.semilla);
+    let resultado = match parametros.tipo {
+        TipoInstancia::Atsp => escribir_atsp(ruta, parametros, &mut rng),
+        TipoInstancia::Tsp => escribir_tsp(ruta, parametros, &mut rng),
+        TipoInstancia::Cvrp => escribir_cvrp(ruta, parametros, &mut rng),
+    };
+    resultado.map_err(|e| format!("No se pudo escribir {}: {}", ruta, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn ruta_temporal(nombre: &str) -> String {
+        std::env::temp_dir()
+            .join(format!("{}_{}", std::process::id(), nombre))
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    fn generar(nombre: &str, parametros: &Parametros) -> Vec<u8> {
+        let ruta = ruta_temporal(nombre);
+        generar_instancia(&ruta, parametros).unwrap();
+        let contenido = fs::read(&ruta).unwrap();
+        fs::remove_file(&ruta).unwrap();
+        contenido
+    }
+
+    #[test]
+    fn misma_semilla_mismo_archivo() {
+        for (tipo, nombre) in [
+            (TipoInstancia::Atsp, "semilla.atsp"),
+            (TipoInstancia::Tsp, "semilla.tsp"),
+            (TipoInstancia::Cvrp, "semilla.vrp"),
+        ] {
+            let mut parametros = Parametros::new(tipo, 20, 7);
+            parametros.grupos = 3;
+            let primero = generar(nombre, &parametros);
+            assert_eq!(primero, generar(nombre, &parametros), "{:?}", tipo);
+
+            parametros.semilla = 8;
+            assert_ne!(primero, generar(nombre, &parametros), "{:?}", tipo);
+        }
+    }
+
+    #[test]
+    fn capacidad_con_la_ocupacion_pedida() {
+        // 100 de demanda en 4 vehiculos al 80 %: ceil(100 / 3.2) = 32
+        let demandas = [20, 25, 25, 30];
+        assert_eq!(capacidad_vehiculo(&demandas, 4, 0.8), 32);
+        // Con 1 vehiculo holgado la mayor demanda manda
+        assert_eq!(capacidad_vehiculo(&[5, 90, 5], 10, 1.0), 90);
+
+        let mut rng = StdRng::seed_from_u64(3);
+        for demanda in [
+            Demanda::Unitaria,
+            Demanda::Uniforme(1, 30),
+            Demanda::Sesgada,
+        ] {
+            for ocupacion in [0.3, 0.75, 0.9, 1.0] {
+                let parametros = Parametros {
+                    demanda,
+                    ocupacion,
+                    ..Parametros::new(TipoInstancia::Cvrp, 40, 0)
+                };
+                let demandas = generar_demandas(&parametros, 39, &mut rng);
+                for vehiculos in [1, 3, 7, 50] {
+                    let capacidad = capacidad_vehiculo(&demandas, vehiculos, ocupacion);
+                    let total: u32 = demandas.iter().sum();
+                    let flota = (capacidad * vehiculos as u32) as f64;
+                    assert!(total as f64 <= ocupacion * flota + 1e-9);
+                    assert!(capacidad >= *demandas.iter().max().unwrap());
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,89 @@
+use generador_instancias::generador::*;
+use std::env;
+
+// Opciones adicionales despues de los argumentos posicionales: --clave valor
+fn leer_opciones(opciones: &[String], parametros: &mut Parametros) -> Result<(), String> {
+    let mut iter = opciones.iter();
+    while let Some(clave) = iter.next() {
+        let valor = iter
+            .next()
+            .ok_or(format!("Falta el valor de la opcion {}", clave))?;
+        let invalido = || format!("Valor invalido para {}: {}", clave, valor);
+        let numero = || valor.parse::<f64>().map_err(|_| invalido());
+        let entero = || valor.parse::<usize>().map_err(|_| invalido());
+        match clave.as_str() {
+            "--lado" => parametros.lado = valor.parse().map_err(|_| invalido())?,
+            "--grupos" => parametros.grupos = entero()?,
+            "--dispersion" => parametros.dispersion = numero()?,
+            "--asimetria" => parametros.asimetria = numero()?,
+            "--ocupacion" => parametros.ocupacion = numero()?,
+            "--vehiculos" => parametros.vehiculos = Some(entero()?),
+            "--demanda" => {
+                parametros.demanda = match valor.as_str() {
+                    "unitaria" => Demanda::Unitaria,
+                    "sesgada" => Demanda::Sesgada,
+                    rango => {
+                        let (min, max) = rango.split_once('-').ok_or(format!(
+                            "Demanda desconocida: {} (unitaria | sesgada | min-max)",
+                            valor
+                        ))?;
+                        Demanda::Uniforme(
+                            min.parse().map_err(|_| invalido())?,
+                            max.parse().map_err(|_| invalido())?,
+                        )
+                    }
+                }
+            }
+            "--deposito" => {
+                parametros.deposito = match valor.as_str() {
+                    "aleatorio" => Deposito::Aleatorio,
+                    "centro" => Deposito::Centro,
+                    "esquina" => Deposito::Esquina,
+                    _ => {
+                        return Err(format!(
+                            "Deposito desconocido: {} (aleatorio | centro | esquina)",
+                            valor
+                        ))
+                    }
+                }
+            }
+            _ => return Err(format!("Opcion desconocida: {}", clave)),
+        }
+    }
+    Ok(())
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+
+    if args.len() < 5 {
+        eprintln!(
+            "Uso: $ ./{} <atsp|tsp|cvrp> <n> <semilla> <archivo_salida> [--lado l] [--grupos k] [--dispersion d] [--asimetria a] [--demanda unitaria|sesgada|min-max] [--ocupacion t] [--vehiculos k] [--deposito aleatorio|centro|esquina]",
+            args[0]
+        );
+        return;
+    }
+    let tipo = match args[1].as_str() {
+        "atsp" => TipoInstancia::Atsp,
+        "tsp" => TipoInstancia::Tsp,
+        "cvrp" => TipoInstancia::Cvrp,
+        _ => {
+            eprintln!("Tipo desconocido: {} (atsp | tsp | cvrp)", args[1]);
+            return;
+        }
+    };
+    let n: usize = args[2].parse().expect("Numero de nodos invalido");
+    let semilla: u64 = args[3].parse().expect("Semilla invalida");
+    let salida = &args[4];
+
+    let mut parametros = Parametros::new(tipo, n, semilla);
+    if let Err(e) = leer_opciones(&args[5..], &mut parametros) {
+        eprintln!("{}", e);
+        return;
+    }
+
+    match generar_instancia(salida, &parametros) {
+        Ok(()) => println!("Instancia {:?} de {} nodos escrita en {}", tipo, n, salida),
+        Err(e) => eprintln!("{}", e),
+    }
+}
@@ -0,0 +1,375 @@
+// Seleccion por ruleta compartida por `sistema_hormiga` y `rutas_vehiculares`.
+//
+// Todas las variantes eligen el indice i con probabilidad p_i = w_i / Σ w_k,
+// donde w son los pesos no normalizados (p. ej. τ_ij^α * η_ij^β).
+//
+// Reglas comunes para pesos degenerados:
+// - Pesos negativos o NaN se tratan como 0 (nunca se eligen).
+// - Si algun peso es infinito (p. ej. distancia 0), se elige de manera
+//   uniforme entre los pesos infinitos.
+// - Si todos los pesos son 0 se elige de manera uniforme entre todos.
+// - Solo se devuelve None si no hay candidatos.
+
+use rand::Rng;
+
+pub type Peso = f64;
+
+fn normalizar_peso(peso: Peso) -> Peso {
+    if peso.is_nan() || peso < 0.0 {
+        0.0
+    } else {
+        peso
+    }
+}
+
+// Eleccion uniforme entre los indices con peso infinito, si existen
+fn seleccion_infinitos<R: Rng + ?Sized>(pesos: &[Peso], rng: &mut R) -> Option<usize> {
+    let total_infinitos = pesos
+        .iter()
+        .filter(|p| p.is_infinite() && **p > 0.0)
+        .count();
+    if total_infinitos == 0 {
+        return None;
+    }
+    let elegido = rng.random_range(0..total_infinitos);
+    pesos
+        .iter()
+        .enumerate()
+        .filter(|(_, p)| p.is_infinite() && **p > 0.0)
+        .nth(elegido)
+        .map(|(i, _)| i)
+}
+
+// Ultimo indice con peso positivo, para absorber errores de redondeo
+fn ultimo_positivo(pesos: &[Peso]) -> Option<usize> {
+    pesos.iter().rposition(|&p| normalizar_peso(p) > 0.0)
+}
+
+/// Ruleta por probabilidad acumulada con busqueda lineal. Adecuada para una
+/// sola extraccion, que es el caso de cada paso de construccion de una hormiga.
+pub fn seleccionar<R: Rng + ?Sized>(pesos: &[Peso], rng: &mut R) -> Option<usize> {
+    if pesos.is_empty() {
+        return None;
+    }
+    if let Some(indice) = seleccion_infinitos(pesos, rng) {
+        return Some(indice);
+    }
+
+    let total: Peso = pesos.iter().map(|&p| normalizar_peso(p)).sum();
+    if total <= 0.0 || !total.is_finite() {
+        return Some(rng.random_range(0..pesos.len()));
+    }
+
+    // Umbral en [0, total): se elige el primer indice cuyo acumulado lo supera
+    let umbral = rng.random::<f64>() * total;
+    let mut acumulado = 0.0;
+    for (indice, &peso) in pesos.iter().enumerate() {
+        let peso = normalizar_peso(peso);
+        if peso == 0.0 {
+            continue;
+        }
+        acumulado += peso;
+        if acumulado > umbral {
+            return Some(indice);
+        }
+    }
+
+    ultimo_positivo(pesos)
+}
+
+/// Ruleta con sumas acumuladas precalculadas: construccion O(n) y cada
+/// extraccion O(log n) por busqueda binaria. Conviene cuando se hacen varias
+/// extracciones sobre la misma distribucion.
+pub struct RuletaAcumulada {
+    acumulados: Vec<Peso>,
+    infinitos: Vec<usize>,
+}
+
+impl RuletaAcumulada {
+    pub fn new(pesos: &[Peso]) -> RuletaAcumulada {
+        let infinitos: Vec<usize> = pesos
+            .iter()
+            .enumerate()
+            .filter(|(_, p)| p.is_infinite() && **p > 0.0)
+            .map(|(i, _)| i)
+            .collect();
+
+        let mut acumulados = Vec::with_capacity(pesos.len());
+        let mut acumulado = 0.0;
+        for &peso in pesos {
+            if peso.is_finite() {
+                acumulado += normalizar_peso(peso);
+            }
+            acumulados.push(acumulado);
+        }
+
+        RuletaAcumulada {
+            acumulados,
+            infinitos,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.acumulados.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.acumulados.is_empty()
+    }
+
+    pub fn seleccionar<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<usize> {
+        if self.acumulados.is_empty() {
+            return None;
+        }
+        if !self.infinitos.is_empty() {
+            return Some(self.infinitos[rng.random_range(0..self.infinitos.len())]);
+        }
+
+        let total = *self.acumulados.last().unwrap();
+        if total <= 0.0 || !total.is_finite() {
+            return Some(rng.random_range(0..self.acumulados.len()));
+        }
+
+        // Primer indice cuyo acumulado supera el umbral; los pesos 0 repiten el
+        // acumulado anterior y por lo tanto nunca se eligen
+        let umbral = rng.random::<f64>() * total;
+        let indice = self.acumulados.partition_point(|&a| a <= umbral);
+        if indice < self.acumulados.len() {
+            Some(indice)
+        } else {
+            // Redondeo: el ultimo indice que aporta peso
+            Some(
+                self.acumulados
+                    .iter()
+                    .rposition(|&a| a < total)
+                    .map_or(0, |i| i + 1),
+            )
+        }
+    }
+}
+
+/// Metodo alias de Walker (variante de Vose): construccion O(n) y cada
+/// extraccion O(1). Conviene para distribuciones fijas y conjuntos grandes.
+pub struct TablaAlias {
+    probabilidades: Vec<f64>,
+    alias: Vec<usize>,
+    infinitos: Vec<usize>,
+}
+
+impl TablaAlias {
+    pub fn new(pesos: &[Peso]) -> TablaAlias {
+        let n = pesos.len();
+        let infinitos: Vec<usize> = pesos
+            .iter()
+            .enumerate()
+            .filter(|(_, p)| p.is_infinite() && **p > 0.0)
+            .map(|(i, _)| i)
+            .collect();
+
+        let normalizados: Vec<Peso> = pesos
+            .iter()
+            .map(|&p| {
+                if p.is_finite() {
+                    normalizar_peso(p)
+                } else {
+                    0.0
+                }
+            })
+            .collect();
+        let total: Peso = normalizados.iter().sum();
+
+        let mut probabilidades = vec![1.0; n];
+        let mut alias: Vec<usize> = (0..n).collect();
+
+        if n == 0 || total <= 0.0 {
+            // Distribucion uniforme: cada columna se queda con su propio indice
+            return TablaAlias {
+                probabilidades,
+                alias,
+                infinitos,
+            };
+        }
+
+        // Escalar para que el promedio sea 1
+        let mut escalados: Vec<f64> = normalizados.iter().map(|p| p * n as f64 / total).collect();
+        let mut pequenos: Vec<usize> = Vec::new();
+        let mut grandes: Vec<usize> = Vec::new();
+        for (i, &p) in escalados.iter().enumerate() {
+            if p < 1.0 {
+                pequenos.push(i);
+            } else {
+                grandes.push(i);
+            }
+        }
+
+        while let (Some(&s), Some(&g)) = (pequenos.last(), grandes.last()) {
+            pequenos.pop();
+            grandes.pop();
+            probabilidades[s] = escalados[s];
+            alias[s] = g;
+            escalados[g] = (escalados[g] + escalados[s]) - 1.0;
+            if escalados[g] < 1.0 {
+                pequenos.push(g);
+            } else {
+                grandes.push(g);
+            }
+        }
+
+        // Lo que queda se debe solo a redondeo: probabilidad 1
+        for i in grandes.into_iter().chain(pequenos) {
+            probabilidades[i] = 1.0;
+            alias[i] = i;
+        }
+
+        TablaAlias {
+            probabilidades,
+            alias,
+            infinitos,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.probabilidades.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.probabilidades.is_empty()
+    }
+
+    pub fn seleccionar<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<usize> {
+        if self.probabilidades.is_empty() {
+            return None;
+        }
+        if !self.infinitos.is_empty() {
+            return Some(self.infinitos[rng.random_range(0..self.infinitos.len())]);
+        }
+
+        let columna = rng.random_range(0..self.probabilidades.len());
+        if rng.random::<f64>() < self.probabilidades[columna] {
+            Some(columna)
+        } else {
+            Some(self.alias[columna])
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    const EXTRACCIONES: usize = 100_000;
+
+    type Extraccion = Box<dyn Fn(&mut StdRng) -> Option<usize>>;
+
+    // Los tres metodos como una sola funcion de extraccion sobre `pesos`
+    fn metodos(pesos: &[Peso]) -> Vec<(&'static str, Extraccion)> {
+        let lineal = pesos.to_vec();
+        let acumulada = RuletaAcumulada::new(pesos);
+        let alias = TablaAlias::new(pesos);
+        vec![
+            (
+                "seleccionar",
+                Box::new(move |rng| seleccionar(&lineal, rng)),
+            ),
+            (
+                "RuletaAcumulada",
+                Box::new(move |rng| acumulada.seleccionar(rng)),
+            ),
+            ("TablaAlias", Box::new(move |rng| alias.seleccionar(rng))),
+        ]
+    }
+
+    fn frecuencias(
+        extraer: &dyn Fn(&mut StdRng) -> Option<usize>,
+        n: usize,
+        semilla: u64,
+    ) -> Vec<usize> {
+        let mut rng = StdRng::seed_from_u64(semilla);
+        let mut conteos = vec![0; n];
+        for _ in 0..EXTRACCIONES {
+            conteos[extraer(&mut rng).unwrap()] += 1;
+        }
+        conteos
+    }
+
+    // Estadistico χ² de los conteos contra las probabilidades esperadas
+    fn chi_cuadrada(conteos: &[usize], esperadas: &[f64]) -> f64 {
+        conteos
+            .iter()
+            .zip(esperadas)
+            .filter(|(_, &p)| p > 0.0)
+            .map(|(&o, &p)| {
+                let e = p * EXTRACCIONES as f64;
+                (o as f64 - e).powi(2) / e
+            })
+            .sum()
+    }
+
+    #[test]
+    fn frecuencias_proporcionales_a_los_pesos() {
+        let pesos = [1.0, 2.0, 3.0, 4.0, 0.5];
+        let total: f64 = pesos.iter().sum();
+        let esperadas: Vec<f64> = pesos.iter().map(|w| w / total).collect();
+        for (nombre, extraer) in metodos(&pesos) {
+            let conteos = frecuencias(extraer.as_ref(), pesos.len(), 7);
+            // χ² con 4 grados de libertad, p = 0.001
+            let chi = chi_cuadrada(&conteos, &esperadas);
+            assert!(chi < 18.47, "{}: χ² = {} con {:?}", nombre, chi, conteos);
+        }
+    }
+
+    #[test]
+    fn pesos_cero_negativos_y_nan_nunca_se_eligen() {
+        let pesos = [0.0, 1.0, -3.0, f64::NAN, 2.0, 0.0];
+        for (nombre, extraer) in metodos(&pesos) {
+            let conteos = frecuencias(extraer.as_ref(), pesos.len(), 11);
+            for i in [0, 2, 3, 5] {
+                assert_eq!(conteos[i], 0, "{}: se eligio el indice {}", nombre, i);
+            }
+            let chi = chi_cuadrada(&conteos, &[0.0, 1.0 / 3.0, 0.0, 0.0, 2.0 / 3.0, 0.0]);
+            // 1 grado de libertad, p = 0.001
+            assert!(chi < 10.83, "{}: χ² = {} con {:?}", nombre, chi, conteos);
+        }
+    }
+
+    #[test]
+    fn infinitos_uniformes_entre_si() {
+        let pesos = [1.0, f64::INFINITY, 1e300, f64::INFINITY, f64::NEG_INFINITY];
+        for (nombre, extraer) in metodos(&pesos) {
+            let conteos = frecuencias(extraer.as_ref(), pesos.len(), 13);
+            assert_eq!(conteos[0] + conteos[2] + conteos[4], 0, "{}", nombre);
+            let chi = chi_cuadrada(&conteos, &[0.0, 0.5, 0.0, 0.5, 0.0]);
+            assert!(chi < 10.83, "{}: χ² = {} con {:?}", nombre, chi, conteos);
+        }
+    }
+
+    #[test]
+    fn todos_cero_da_eleccion_uniforme() {
+        let pesos = [0.0; 4];
+        for (nombre, extraer) in metodos(&pesos) {
+            let conteos = frecuencias(extraer.as_ref(), pesos.len(), 17);
+            // 3 grados de libertad, p = 0.001
+            let chi = chi_cuadrada(&conteos, &[0.25; 4]);
+            assert!(chi < 16.27, "{}: χ² = {} con {:?}", nombre, chi, conteos);
+        }
+    }
+
+    #[test]
+    fn sin_pesos_no_hay_eleccion() {
+        let mut rng = StdRng::seed_from_u64(1);
+        for (nombre, extraer) in metodos(&[]) {
+            assert_eq!(extraer(&mut rng), None, "{}", nombre);
+        }
+    }
+
+    #[test]
+    fn un_solo_peso_positivo_siempre_se_elige() {
+        let pesos = [0.0, 0.0, 5.0, 0.0];
+        for (nombre, extraer) in metodos(&pesos) {
+            let conteos = frecuencias(extraer.as_ref(), pesos.len(), 19);
+            assert_eq!(conteos[2], EXTRACCIONES, "{}", nombre);
+        }
+    }
+}
@@ -15,7 +15,7 @@ PROJECT_DIR="rutas_vehiculares"
 
 # Parámetros para el algoritmo (se reciben como argumentos)
 if [ $# -lt 3 ]; then
-    echo "Uso: $0 <n_hormigas> <rho> <max_iteraciones>"
+    echo "Uso: $0 <n_hormigas> <rho> <max_iteraciones> [opciones, p. ej. --visibilidad ahorros]"
     exit 1
 fi
 N_HORMIGAS=$1
@@ -73,7 +73,7 @@ for filepath in "$FILES_DIR"/*.vrp; do
         # Ejecutar con 'nice' (opcional) y redirigir salida a logs  
         ./target/release/rutas_vehiculares \
             $N_HORMIGAS $RHO $MAX_ITERACIONES $IMPORTANCIA_DISTANCIA $IMPORTANCIA_FEROMONA \
-            "$filepath" test >"logs/${file_stem}.log" 2>&1 &
+            "$filepath" test "$@" >"logs/${file_stem}.log" 2>&1 &
 
         # Esperar si hay demasiados jobs corriendo
         while [ "$(jobs -p | wc -l)" -ge "$MAX_JOBS" ]; do
@@ -0,0 +1,332 @@
+use crate::rutas_vehiculares::*;
+
+// Heuristica de ahorros de Clarke y Wright. Se parte de una ruta
+// deposito-i-deposito por cliente; unir el final de la ruta de i con el
+// inicio de la ruta de j ahorra s_ij = d_0i + d_0j - d_ij. Los pares se
+// recorren de mayor a menor ahorro y se unen si ambos son extremos de sus
+// rutas y la carga combinada cabe en el vehiculo.
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum VarianteAhorros {
+    // Todas las rutas crecen a la vez
+    Paralela,
+    // Una ruta crece hasta que ningun ahorro la extiende, luego la siguiente
+    Secuencial,
+}
+
+// Pares (i, j) de clientes con su ahorro, de mayor a menor; solo i < j
+// porque las distancias son simetricas
+fn lista_ahorros(
+    cd: &ConjuntoDistancias,
+    clientes: &[NodoId],
+    deposito: NodoId,
+) -> Vec<(NodoId, NodoId, Distancia)> {
+    let d = |a: NodoId, b: NodoId| cd[(a - 1) as usize][(b - 1) as usize];
+    let mut ahorros = Vec::with_capacity(clientes.len() * clientes.len() / 2);
+    for (k, &i) in clientes.iter().enumerate() {
+        for &j in &clientes[k + 1..] {
+            ahorros.push((i, j, d(deposito, i) + d(deposito, j) - d(i, j)));
+        }
+    }
+    // Empates por ids para que el resultado sea determinista
+    ahorros.sort_by(|a, b| b.2.total_cmp(&a.2).then((a.0, a.1).cmp(&(b.0, b.1))));
+    ahorros
+}
+
+// Une `b` al final de `a` de modo que `i` (extremo de a) quede junto a `j`
+// (extremo de b); las rutas no incluyen al deposito
+fn unir_rutas(mut a: Vec<NodoId>, mut b: Vec<NodoId>, i: NodoId, j: NodoId) -> Vec<NodoId> {
+    if a.first() == Some(&i) {
+        a.reverse();
+    }
+    if b.last() == Some(&j) {
+        b.reverse();
+    }
+    a.extend(b);
+    a
+}
+
+fn es_extremo(ruta: &[NodoId], nodo: NodoId) -> bool {
+    ruta.first() == Some(&nodo) || ruta.last() == Some(&nodo)
+}
+
+fn ahorros_paralelo(
+    ahorros: &[(NodoId, NodoId, Distancia)],
+    clientes: &[NodoId],
+    demandas: &[u32],
+    capacidad: u32,
+) -> Vec<Vec<NodoId>> {
+    // Ruta a la que pertenece cada cliente (indice en `rutas`) y su carga
+    let mut ruta_de = vec![usize::MAX; demandas.len()];
+    let mut rutas: Vec<Option<Vec<NodoId>>> = Vec::with_capacity(clientes.len());
+    let mut cargas: Vec<u32> = Vec::with_capacity(clientes.len());
+    for &cliente in clientes {
+        ruta_de[cliente as usize] = rutas.len();
+        rutas.push(Some(vec![cliente]));
+        cargas.push(demandas[cliente as usize]);
+    }
+
+    for &(i, j, ahorro) in ahorros {
+        if ahorro <= 0.0 {
+            break;
+        }
+        let (ri, rj) = (ruta_de[i as usize], ruta_de[j as usize]);
+        if ri == rj || cargas[ri] + cargas[rj] > capacidad {
+            continue;
+        }
+        let (Some(a), Some(b)) = (&rutas[ri], &rutas[rj]) else {
+            continue;
+        };
+        if !es_extremo(a, i) || !es_extremo(b, j) {
+            continue;
+        }
+
+        let unida = unir_rutas(rutas[ri].take().unwrap(), rutas[rj].take().unwrap(), i, j);
+        for &cliente in &unida {
+            ruta_de[cliente as usize] = ri;
+        }
+        cargas[ri] += cargas[rj];
+        rutas[ri] = Some(unida);
+    }
+    rutas.into_iter().flatten().collect()
+}
+
+fn ahorros_secuencial(
+    ahorros: &[(NodoId, NodoId, Distancia)],
+    clientes: &[NodoId],
+    demandas: &[u32],
+    capacidad: u32,
+) -> Vec<Vec<NodoId>> {
+    let mut asignado = vec![false; demandas.len()];
+    let mut rutas: Vec<Vec<NodoId>> = Vec::new();
+
+    // Semilla de cada ruta: el mayor ahorro entre dos clientes libres que
+    // caben juntos
+    while let Some(&(i, j, _)) = ahorros.iter().find(|&&(i, j, ahorro)| {
+        ahorro > 0.0
+            && !asignado[i as usize]
+            && !asignado[j as usize]
+            && demandas[i as usize] + demandas[j as usize] <= capacidad
+    }) {
+        let mut ruta = vec![i, j];
+        let mut carga = demandas[i as usize] + demandas[j as usize];
+        asignado[i as usize] = true;
+        asignado[j as usize] = true;
+
+        // Extender por cualquiera de los extremos con el mayor ahorro posible
+        loop {
+            let siguiente = ahorros
+                .iter()
+                .filter(|&&(_, _, ahorro)| ahorro > 0.0)
+                .filter_map(|&(i, j, _)| {
+                    if es_extremo(&ruta, i) && !asignado[j as usize] {
+                        Some((i, j))
+                    } else if es_extremo(&ruta, j) && !asignado[i as usize] {
+                        Some((j, i))
+                    } else {
+                        None
+                    }
+                })
+                .find(|&(_, nuevo)| carga + demandas[nuevo as usize] <= capacidad);
+            let Some((extremo, nuevo)) = siguiente else {
+                break;
+            };
+            if ruta.last() == Some(&extremo) {
+                ruta.push(nuevo);
+            } else {
+                ruta.insert(0, nuevo);
+            }
+            carga += demandas[nuevo as usize];
+            asignado[nuevo as usize] = true;
+        }
+        rutas.push(ruta);
+    }
+
+    // Los clientes sin ahorro positivo con nadie quedan en rutas propias
+    for &cliente in clientes {
+        if !asignado[cliente as usize] {
+            rutas.push(vec![cliente]);
+        }
+    }
+    rutas
+}
+
+pub fn ahorros_clarke_wright(
+    datos: &DatosVRP,
+    cd: &ConjuntoDistancias,
+    variante: VarianteAhorros,
+) -> Solucion {
+    let deposito = datos.deposito_id;
+    let clientes: Vec<NodoId> = datos
+        .clientes
+        .iter()
+        .map(|c| c.id)
+        .filter(|&id| id != deposito)
+        .collect();
+    let demandas = demandas_por_id(&datos.clientes);
+
+    let ahorros = lista_ahorros(cd, &clientes, deposito);
+    let rutas = match variante {
+        VarianteAhorros::Paralela => {
+            ahorros_paralelo(&ahorros, &clientes, &demandas, datos.capacidad)
+        }
+        VarianteAhorros::Secuencial => {
+            ahorros_secuencial(&ahorros, &clientes, &demandas, datos.capacidad)
+        }
+    };
+
+    // Agregar el deposito al inicio y al final de cada ruta
+    let rutas: Vec<Camino> = rutas
+        .into_iter()
+        .map(|ruta| {
+            let mut camino = Vec::with_capacity(ruta.len() + 2);
+            camino.push(deposito);
+            camino.extend(ruta);
+            camino.push(deposito);
+            camino
+        })
+        .collect();
+    let evaluacion = evaluar_rutas(
+        &rutas,
+        cd,
+        datos.capacidad,
+        datos.n_vehiculos,
+        &datos.clientes,
+        deposito,
+    );
+    Solucion {
+        costo_total: evaluacion.costo_total,
+        vehiculos_usados: evaluacion.vehiculos_usados,
+        rutas,
+    }
+}
+
+// Rastros iniciales sembrados con una solucion de ahorros: todas las aristas
+// reciben τ0 = 1 / (n * L) y las de la solucion ademas 1 / L, como si la
+// solucion ya hubiera depositado una vez
+pub fn inicializacion_ahorros(ca: &ConjuntoAristas, solucion: &Solucion) -> ConjuntoFeromonas {
+    let costo = solucion.costo_total.max(1) as Feromona;
+    let tau0 = 1.0 / (ca.len().max(1) as Feromona * costo);
+    let mut cf = inicializacion_uniforme(ca, tau0);
+    for ruta in &solucion.rutas {
+        for ventana in ruta.windows(2) {
+            let origen_indice = (ventana[0] - 1) as usize;
+            let destino_indice = (ventana[1] - 1) as usize;
+            cf[origen_indice][destino_indice] += 1.0 / costo;
+            cf[destino_indice][origen_indice] += 1.0 / costo;
+        }
+    }
+    cf
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Deposito 1 y clientes 2..=5 con demanda 1 y capacidad 3. Todos los
+    // clientes estan a 10 del deposito; los ahorros quedan
+    // s(2,3) = 15 > s(4,5) = 14 > s(3,4) = 13 > el resto.
+    fn instancia() -> (DatosVRP, ConjuntoDistancias) {
+        let cd = vec![
+            vec![0.0, 10.0, 10.0, 10.0, 10.0],
+            vec![10.0, 0.0, 5.0, 12.0, 14.0],
+            vec![10.0, 5.0, 0.0, 7.0, 13.0],
+            vec![10.0, 12.0, 7.0, 0.0, 6.0],
+            vec![10.0, 14.0, 13.0, 6.0, 0.0],
+        ];
+        let clientes = [(1, 0), (2, 1), (3, 1), (4, 1), (5, 1)]
+            .into_iter()
+            .map(|(id, demanda)| Cliente { id, demanda })
+            .collect();
+        let datos = DatosVRP {
+            nodos: Vec::new(),
+            dimension: 5,
+            capacidad: 3,
+            deposito_id: 1,
+            clientes,
+            n_vehiculos: 3,
+        };
+        (datos, cd)
+    }
+
+    fn comprobar_solucion(datos: &DatosVRP, solucion: &Solucion) {
+        let demandas = demandas_por_id(&datos.clientes);
+        let mut visitas = vec![0; demandas.len()];
+        for ruta in &solucion.rutas {
+            assert_eq!(ruta.first(), Some(&datos.deposito_id));
+            assert_eq!(ruta.last(), Some(&datos.deposito_id));
+            let interior = &ruta[1..ruta.len() - 1];
+            let carga: u32 = interior.iter().map(|&c| demandas[c as usize]).sum();
+            assert!(
+                carga <= datos.capacidad,
+                "ruta {:?} con carga {}",
+                ruta,
+                carga
+            );
+            for &cliente in interior {
+                visitas[cliente as usize] += 1;
+            }
+        }
+        for cliente in datos.clientes.iter().filter(|c| c.id != datos.deposito_id) {
+            assert_eq!(visitas[cliente.id as usize], 1, "cliente {}", cliente.id);
+        }
+    }
+
+    #[test]
+    fn paralela_une_los_dos_mejores_pares() {
+        let (datos, cd) = instancia();
+        let solucion = ahorros_clarke_wright(&datos, &cd, VarianteAhorros::Paralela);
+        comprobar_solucion(&datos, &solucion);
+        // 1-2-3-1 = 25 y 1-4-5-1 = 26; unir 3 con 4 excede la capacidad
+        assert_eq!(solucion.costo_total, 51);
+        assert_eq!(solucion.vehiculos_usados, 2);
+    }
+
+    #[test]
+    fn secuencial_extiende_la_primera_ruta_hasta_llenarla() {
+        let (datos, cd) = instancia();
+        let solucion = ahorros_clarke_wright(&datos, &cd, VarianteAhorros::Secuencial);
+        comprobar_solucion(&datos, &solucion);
+        // 1-2-3-4-1 = 32 consume al cliente 4 y el 5 queda solo: 1-5-1 = 20
+        assert_eq!(solucion.costo_total, 52);
+        assert_eq!(solucion.vehiculos_usados, 2);
+    }
+
+    #[test]
+    fn ambas_variantes_atienden_a_todos_en_una_instancia_real() {
+        let datos = leer_matriz("src/files/A-n32-k5.vrp".to_string());
+        let cd = calcular_conjunto_distancias(&datos);
+        for variante in [VarianteAhorros::Paralela, VarianteAhorros::Secuencial] {
+            let solucion = ahorros_clarke_wright(&datos, &cd, variante);
+            comprobar_solucion(&datos, &solucion);
+            assert_ne!(solucion.costo_total, u32::MAX);
+        }
+    }
+
+    #[test]
+    fn la_siembra_refuerza_las_aristas_de_la_solucion() {
+        let (datos, cd) = instancia();
+        let solucion = ahorros_clarke_wright(&datos, &cd, VarianteAhorros::Paralela);
+        let ca = create_conjunto_aristas(5);
+        let cf = inicializacion_ahorros(&ca, &solucion);
+
+        let tau0 = 1.0 / (5.0 * 51.0);
+        let mut en_solucion = [[false; 5]; 5];
+        for ruta in &solucion.rutas {
+            for ventana in ruta.windows(2) {
+                let (a, b) = ((ventana[0] - 1) as usize, (ventana[1] - 1) as usize);
+                en_solucion[a][b] = true;
+                en_solucion[b][a] = true;
+            }
+        }
+        for i in 0..5 {
+            for j in (0..5).filter(|&j| j != i) {
+                if en_solucion[i][j] {
+                    assert!((cf[i][j] - (tau0 + 1.0 / 51.0)).abs() < 1e-12);
+                } else {
+                    assert!((cf[i][j] - tau0).abs() < 1e-12);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,666 @@
+use crate::rutas_vehiculares::{Camino, ConjuntoDistancias, Hormigas, NodoId};
+
+// Busqueda local para soluciones del CVRP. Cada ruta es deposito, clientes,
+// deposito. Los movimientos solo cambian el orden y la asignacion de los
+// clientes, asi que ninguno agrega vehiculos; uno que vacia una ruta la
+// elimina. El costo de cada arista se trunca igual que en `evaluar_rutas`,
+// de modo que toda mejora aceptada baja el costo reportado.
+
+// A que hormigas se aplica la busqueda local en cada iteracion
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BusquedaLocal {
+    Ninguna,
+    TodasLasHormigas,
+    MejorHormiga,
+}
+
+// Que movimiento se aplica en cada paso, entre los que involucran a la ruta
+// en turno
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CriterioMejora {
+    // El primero que mejora, recorriendo los vecindarios en orden: 2-opt,
+    // reubicacion, intercambio, 2-opt* e intercambio cruzado
+    PrimeraMejora,
+    // El que mas mejora entre todos los vecindarios
+    MejorMejora,
+}
+
+// Longitud maxima de los segmentos del intercambio cruzado
+const LONGITUD_CRUZADO: usize = 3;
+
+#[derive(Debug, Clone, Copy)]
+enum Movimiento {
+    // Invertir ruta[i + 1..=j]
+    DosOpt {
+        ruta: usize,
+        i: usize,
+        j: usize,
+    },
+    // Sacar el cliente `posicion` de `origen` e insertarlo en `destino`
+    // antes del nodo en `insercion`
+    Reubicar {
+        origen: usize,
+        posicion: usize,
+        destino: usize,
+        insercion: usize,
+    },
+    // Cortar despues de a[i] y de b[j] e intercambiar las colas
+    DosOptEstrella {
+        a: usize,
+        i: usize,
+        b: usize,
+        j: usize,
+    },
+    // Intercambiar a[ia..=ja] con b[ib..=jb] sin invertirlos; con un cliente
+    // por lado es el intercambio simple
+    Cruzado {
+        a: usize,
+        ia: usize,
+        ja: usize,
+        b: usize,
+        ib: usize,
+        jb: usize,
+    },
+}
+
+// Datos fijos de la busqueda
+struct Instancia<'a> {
+    cd: &'a ConjuntoDistancias,
+    demandas: &'a [u32],
+    capacidad: u32,
+}
+
+impl Instancia<'_> {
+    fn d(&self, a: NodoId, b: NodoId) -> i64 {
+        self.cd[(a - 1) as usize][(b - 1) as usize] as u32 as i64
+    }
+
+    fn demanda(&self, nodo: NodoId) -> u32 {
+        self.demandas[nodo as usize]
+    }
+
+    // Carga acumulada: prefijos[k] = demanda de ruta[0..k]
+    fn prefijos(&self, ruta: &[NodoId]) -> Vec<u32> {
+        let mut prefijos = Vec::with_capacity(ruta.len() + 1);
+        prefijos.push(0);
+        for &nodo in ruta {
+            prefijos.push(prefijos.last().unwrap() + self.demanda(nodo));
+        }
+        prefijos
+    }
+}
+
+// Rutas con su carga y sus cargas acumuladas. Un movimiento solo recalcula
+// las rutas que toca. Una ruta activa aun puede tener movimientos que
+// mejoran; se desactiva cuando ninguno de los que la involucran mejora y se
+// reactiva cuando un movimiento la cambia. Sin rutas activas la solucion es
+// un optimo local de todos los vecindarios.
+struct Solucion<'a> {
+    rutas: &'a mut Vec<Camino>,
+    cargas: Vec<u32>,
+    prefijos: Vec<Vec<u32>>,
+    activas: Vec<bool>,
+}
+
+impl<'a> Solucion<'a> {
+    fn new(rutas: &'a mut Vec<Camino>, inst: &Instancia) -> Solucion<'a> {
+        let prefijos: Vec<Vec<u32>> = rutas.iter().map(|ruta| inst.prefijos(ruta)).collect();
+        let cargas = prefijos.iter().map(|p| *p.last().unwrap()).collect();
+        let activas = vec![true; rutas.len()];
+        Solucion {
+            rutas,
+            cargas,
+            prefijos,
+            activas,
+        }
+    }
+
+    // Primera ruta activa a partir de `inicio`, dando la vuelta
+    fn siguiente_activa(&self, inicio: usize) -> Option<usize> {
+        let n = self.rutas.len();
+        (0..n).map(|k| (inicio + k) % n).find(|&r| self.activas[r])
+    }
+
+    // Aplica el movimiento, recalcula y reactiva las rutas que toca y quita
+    // la que haya quedado sin clientes. Devuelve el indice de la ruta quitada.
+    fn aplicar(&mut self, movimiento: Movimiento, inst: &Instancia) -> Option<usize> {
+        let (a, b) = aplicar_movimiento(self.rutas, movimiento);
+        for r in [a, b] {
+            self.prefijos[r] = inst.prefijos(&self.rutas[r]);
+            self.cargas[r] = *self.prefijos[r].last().unwrap();
+            self.activas[r] = true;
+        }
+        // Ningun movimiento vacia las dos rutas
+        let vacia = [a, b].into_iter().find(|&r| self.rutas[r].len() <= 2)?;
+        self.rutas.remove(vacia);
+        self.cargas.remove(vacia);
+        self.prefijos.remove(vacia);
+        self.activas.remove(vacia);
+        Some(vacia)
+    }
+}
+
+// Movimiento elegido segun el criterio. `ofrecer` devuelve true cuando la
+// busqueda puede detenerse (primera mejora encontrada).
+struct Seleccion {
+    criterio: CriterioMejora,
+    elegido: Option<(i64, Movimiento)>,
+}
+
+impl Seleccion {
+    fn ofrecer(&mut self, delta: i64, movimiento: Movimiento) -> bool {
+        if delta < 0 && self.elegido.is_none_or(|(mejor, _)| delta < mejor) {
+            self.elegido = Some((delta, movimiento));
+        }
+        self.criterio == CriterioMejora::PrimeraMejora && self.elegido.is_some()
+    }
+}
+
+// 2-opt dentro de la ruta r: quitar (r[i], r[i+1]) y (r[j], r[j+1]) e
+// invertir el tramo entre ellas
+fn explorar_dos_opt(
+    r: usize,
+    solucion: &Solucion,
+    inst: &Instancia,
+    seleccion: &mut Seleccion,
+) -> bool {
+    let ruta = &solucion.rutas[r];
+    let n = ruta.len();
+    for i in 0..n.saturating_sub(3) {
+        for j in i + 2..n - 1 {
+            let delta = inst.d(ruta[i], ruta[j]) + inst.d(ruta[i + 1], ruta[j + 1])
+                - inst.d(ruta[i], ruta[i + 1])
+                - inst.d(ruta[j], ruta[j + 1]);
+            if seleccion.ofrecer(delta, Movimiento::DosOpt { ruta: r, i, j }) {
+                return true;
+            }
+        }
+    }
+    false
+}
+
+// Mover un cliente de `origen` a `destino`, si cabe
+fn reubicar_entre(
+    origen: usize,
+    destino: usize,
+    solucion: &Solucion,
+    inst: &Instancia,
+    seleccion: &mut Seleccion,
+) -> bool {
+    let (ruta, otra) = (&solucion.rutas[origen], &solucion.rutas[destino]);
+    for posicion in 1..ruta.len() - 1 {
+        let (anterior, cliente, siguiente) =
+            (ruta[posicion - 1], ruta[posicion], ruta[posicion + 1]);
+        if solucion.cargas[destino] + inst.demanda(cliente) > inst.capacidad {
+            continue;
+        }
+        let ganancia =
+            inst.d(anterior, cliente) + inst.d(cliente, siguiente) - inst.d(anterior, siguiente);
+        for k in 0..otra.len() - 1 {
+            let costo = inst.d(otra[k], cliente) + inst.d(cliente, otra[k + 1])
+                - inst.d(otra[k], otra[k + 1]);
+            let movimiento = Movimiento::Reubicar {
+                origen,
+                posicion,
+                destino,
+                insercion: k + 1,
+            };
+            if seleccion.ofrecer(costo - ganancia, movimiento) {
+                return true;
+            }
+        }
+    }
+    false
+}
+
+// Reubicacion: sacar un cliente de la ruta r o meterlo en ella
+fn explorar_reubicacion(
+    r: usize,
+    solucion: &Solucion,
+    inst: &Instancia,
+    seleccion: &mut Seleccion,
+) -> bool {
+    for otra in (0..solucion.rutas.len()).filter(|&otra| otra != r) {
+        if reubicar_entre(r, otra, solucion, inst, seleccion)
+            || reubicar_entre(otra, r, solucion, inst, seleccion)
+        {
+            return true;
+        }
+    }
+    false
+}
+
+// Intercambio cruzado de a[ia..ia + la] con b[ib..ib + lb], con a = r, b
+// cualquier otra ruta y las longitudes la y lb entre `minimo` y `maximo`
+fn explorar_cruzado(
+    r: usize,
+    solucion: &Solucion,
+    inst: &Instancia,
+    longitudes: (usize, usize),
+    seleccion: &mut Seleccion,
+) -> bool {
+    let (minimo, maximo) = longitudes;
+    let a = r;
+    let (ra, pa) = (&solucion.rutas[a], &solucion.prefijos[a]);
+    for b in (0..solucion.rutas.len()).filter(|&b| b != a) {
+        let (rb, pb) = (&solucion.rutas[b], &solucion.prefijos[b]);
+        for la in minimo..=maximo {
+            for lb in minimo..=maximo {
+                // El caso 1-1 ya lo cubre el intercambio simple
+                if maximo > 1 && la == 1 && lb == 1 {
+                    continue;
+                }
+                for ia in 1..ra.len().saturating_sub(la) {
+                    let ja = ia + la - 1;
+                    let carga_a = pa[ja + 1] - pa[ia];
+                    for ib in 1..rb.len().saturating_sub(lb) {
+                        let jb = ib + lb - 1;
+                        let carga_b = pb[jb + 1] - pb[ib];
+                        if solucion.cargas[a] - carga_a + carga_b > inst.capacidad
+                            || solucion.cargas[b] - carga_b + carga_a > inst.capacidad
+                        {
+                            continue;
+                        }
+                        let delta = inst.d(ra[ia - 1], rb[ib])
+                            + inst.d(rb[jb], ra[ja + 1])
+                            + inst.d(rb[ib - 1], ra[ia])
+                            + inst.d(ra[ja], rb[jb + 1])
+                            - inst.d(ra[ia - 1], ra[ia])
+                            - inst.d(ra[ja], ra[ja + 1])
+                            - inst.d(rb[ib - 1], rb[ib])
+                            - inst.d(rb[jb], rb[jb + 1]);
+                        let movimiento = Movimiento::Cruzado {
+                            a,
+                            ia,
+                            ja,
+                            b,
+                            ib,
+                            jb,
+                        };
+                        if seleccion.ofrecer(delta, movimiento) {
+                            return true;
+                        }
+                    }
+                }
+            }
+        }
+    }
+    false
+}
+
+// 2-opt*: a = A1 A2 y b = B1 B2 pasan a A1 B2 y B1 A2, con a = r
+fn explorar_dos_opt_estrella(
+    r: usize,
+    solucion: &Solucion,
+    inst: &Instancia,
+    seleccion: &mut Seleccion,
+) -> bool {
+    let a = r;
+    let (ra, pa) = (&solucion.rutas[a], &solucion.prefijos[a]);
+    for b in (0..solucion.rutas.len()).filter(|&b| b != a) {
+        let (rb, pb) = (&solucion.rutas[b], &solucion.prefijos[b]);
+        for i in 0..ra.len() - 1 {
+            for j in 0..rb.len() - 1 {
+                let nueva_a = pa[i + 1] + solucion.cargas[b] - pb[j + 1];
+                let nueva_b = pb[j + 1] + solucion.cargas[a] - pa[i + 1];
+                if nueva_a > inst.capacidad || nueva_b > inst.capacidad {
+                    continue;
+                }
+                let delta = inst.d(ra[i], rb[j + 1]) + inst.d(rb[j], ra[i + 1])
+                    - inst.d(ra[i], ra[i + 1])
+                    - inst.d(rb[j], rb[j + 1]);
+                if seleccion.ofrecer(delta, Movimiento::DosOptEstrella { a, i, b, j }) {
+                    return true;
+                }
+            }
+        }
+    }
+    false
+}
+
+// Aplica el movimiento y devuelve las dos rutas que cambia (la misma dos
+// veces en el 2-opt)
+fn aplicar_movimiento(rutas: &mut [Camino], movimiento: Movimiento) -> (usize, usize) {
+    match movimiento {
+        Movimiento::DosOpt { ruta, i, j } => {
+            rutas[ruta][i + 1..=j].reverse();
+            (ruta, ruta)
+        }
+        Movimiento::Reubicar {
+            origen,
+            posicion,
+            destino,
+            insercion,
+        } => {
+            let cliente = rutas[origen].remove(posicion);
+            rutas[destino].insert(insercion, cliente);
+            (origen, destino)
+        }
+        Movimiento::DosOptEstrella { a, i, b, j } => {
+            let cola_a = rutas[a].split_off(i + 1);
+            let cola_b = rutas[b].split_off(j + 1);
+            rutas[a].extend(cola_b);
+            rutas[b].extend(cola_a);
+            (a, b)
+        }
+        Movimiento::Cruzado {
+            a,
+            ia,
+            ja,
+            b,
+            ib,
+            jb,
+        } => {
+            let segmento_a: Camino = rutas[a][ia..=ja].to_vec();
+            let segmento_b: Camino = rutas[b].splice(ib..=jb, segmento_a).collect();
+            rutas[a].splice(ia..=ja, segmento_b);
+            (a, b)
+        }
+    }
+}
+
+// Aplica los movimientos hasta llegar a un optimo local de todos los
+// vecindarios. Las rutas deben empezar y terminar en el deposito. Se
+// recorren las rutas activas en orden circular; tras una mejora se sigue
+// con la misma ruta en lugar de volver a la primera.
+pub fn mejorar_rutas(
+    rutas: &mut Vec<Camino>,
+    cd: &ConjuntoDistancias,
+    demandas: &[u32],
+    capacidad: u32,
+    criterio: CriterioMejora,
+) {
+    let inst = Instancia {
+        cd,
+        demandas,
+        capacidad,
+    };
+    let mut solucion = Solucion::new(rutas, &inst);
+    let mut cursor = 0;
+    while let Some(r) = solucion.siguiente_activa(cursor) {
+        let mut seleccion = Seleccion {
+            criterio,
+            elegido: None,
+        };
+        // Con primera mejora el || corta en el primer vecindario que mejora
+        let _ = explorar_dos_opt(r, &solucion, &inst, &mut seleccion)
+            || explorar_reubicacion(r, &solucion, &inst, &mut seleccion)
+            || explorar_cruzado(r, &solucion, &inst, (1, 1), &mut seleccion)
+            || explorar_dos_opt_estrella(r, &solucion, &inst, &mut seleccion)
+            || explorar_cruzado(r, &solucion, &inst, (1, LONGITUD_CRUZADO), &mut seleccion);
+        cursor = match seleccion.elegido {
+            Some((_, movimiento)) => match solucion.aplicar(movimiento, &inst) {
+                Some(quitada) if quitada < r => r - 1,
+                _ => r,
+            },
+            None => {
+                solucion.activas[r] = false;
+                r + 1
+            }
+        };
+    }
+}
+
+fn costo_rutas(rutas: &[Camino], cd: &ConjuntoDistancias) -> u32 {
+    rutas
+        .iter()
+        .flat_map(|ruta| ruta.windows(2))
+        .map(|ventana| cd[(ventana[0] - 1) as usize][(ventana[1] - 1) as usize] as u32)
+        .sum()
+}
+
+pub fn aplicar_busqueda_local(
+    hormigas: &mut Hormigas,
+    cd: &ConjuntoDistancias,
+    demandas: &[u32],
+    capacidad: u32,
+    busqueda_local: BusquedaLocal,
+    criterio: CriterioMejora,
+) {
+    match busqueda_local {
+        BusquedaLocal::Ninguna => {}
+        BusquedaLocal::TodasLasHormigas => {
+            for hormiga in hormigas.iter_mut() {
+                mejorar_rutas(&mut hormiga.rutas, cd, demandas, capacidad, criterio);
+            }
+        }
+        BusquedaLocal::MejorHormiga => {
+            if let Some(hormiga) = hormigas
+                .iter_mut()
+                .min_by_key(|hormiga| costo_rutas(&hormiga.rutas, cd))
+            {
+                mejorar_rutas(&mut hormiga.rutas, cd, demandas, capacidad, criterio);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rutas_vehiculares::{evaluar_rutas, Cliente};
+
+    const DEPOSITO: NodoId = 1;
+
+    // Distancias euclidianas entre puntos; el deposito es el primero
+    fn distancias(puntos: &[(f64, f64)]) -> ConjuntoDistancias {
+        puntos
+            .iter()
+            .map(|a| {
+                puntos
+                    .iter()
+                    .map(|b| ((a.0 - b.0).powi(2) + (a.1 - b.1).powi(2)).sqrt())
+                    .collect()
+            })
+            .collect()
+    }
+
+    // Deposito con demanda 0 y los clientes de las rutas con demanda 1
+    fn clientes(rutas: &[Camino]) -> Vec<Cliente> {
+        let deposito = Cliente {
+            id: DEPOSITO,
+            demanda: 0,
+        };
+        std::iter::once(deposito)
+            .chain(
+                clientes_visitados(rutas)
+                    .into_iter()
+                    .map(|id| Cliente { id, demanda: 1 }),
+            )
+            .collect()
+    }
+
+    fn demandas(n: u32) -> Vec<u32> {
+        let mut demandas = vec![1; n as usize + 1];
+        demandas[0] = 0;
+        demandas[DEPOSITO as usize] = 0;
+        demandas
+    }
+
+    fn clientes_visitados(rutas: &[Camino]) -> Vec<NodoId> {
+        let mut visitados: Vec<NodoId> = rutas
+            .iter()
+            .flat_map(|ruta| ruta[1..ruta.len() - 1].iter().copied())
+            .collect();
+        visitados.sort_unstable();
+        visitados
+    }
+
+    // Aplica el mejor movimiento de un vecindario con la ruta 0 en turno y
+    // comprueba que conserva los clientes, respeta la capacidad y baja el
+    // costo de `evaluar_rutas`
+    fn aplicar_vecindario(
+        rutas: &mut Vec<Camino>,
+        cd: &ConjuntoDistancias,
+        capacidad: u32,
+        explorar: impl Fn(usize, &Solucion, &Instancia, &mut Seleccion) -> bool,
+    ) -> Movimiento {
+        let n = cd.len() as u32;
+        let (clientes, demandas) = (clientes(rutas), demandas(n));
+        let inst = Instancia {
+            cd,
+            demandas: &demandas,
+            capacidad,
+        };
+        let antes = rutas.clone();
+
+        let mut solucion = Solucion::new(rutas, &inst);
+        let mut seleccion = Seleccion {
+            criterio: CriterioMejora::MejorMejora,
+            elegido: None,
+        };
+        explorar(0, &solucion, &inst, &mut seleccion);
+        let (_, movimiento) = seleccion.elegido.expect("ningun movimiento mejora");
+        solucion.aplicar(movimiento, &inst);
+
+        assert_eq!(clientes_visitados(rutas), clientes_visitados(&antes));
+        let evaluar =
+            |rutas: &[Camino]| evaluar_rutas(rutas, cd, capacidad, n, &clientes, DEPOSITO);
+        let (previa, nueva) = (evaluar(&antes), evaluar(rutas));
+        assert!(nueva.es_factible, "{:?}", nueva.violaciones);
+        assert!(nueva.costo_total < previa.costo_total);
+        movimiento
+    }
+
+    // Dos grupos de clientes lejos del deposito: al este (2, 3, 4) y al
+    // norte (5, 6, 7)
+    fn grupos() -> ConjuntoDistancias {
+        distancias(&[
+            (0.0, 0.0),
+            (50.0, 0.0),
+            (51.0, 0.0),
+            (52.0, 0.0),
+            (0.0, 50.0),
+            (0.0, 51.0),
+            (0.0, 52.0),
+        ])
+    }
+
+    #[test]
+    fn dos_opt_deshace_un_cruce() {
+        let cd = distancias(&[(0.0, 0.0), (0.0, 10.0), (10.0, 10.0), (10.0, 0.0)]);
+        let mut rutas = vec![vec![1, 2, 4, 3, 1]];
+        let movimiento = aplicar_vecindario(&mut rutas, &cd, 3, explorar_dos_opt);
+        assert!(matches!(movimiento, Movimiento::DosOpt { .. }));
+        assert_eq!(rutas, vec![vec![1, 2, 3, 4, 1]]);
+    }
+
+    #[test]
+    fn reubicacion_mueve_un_cliente_a_su_grupo() {
+        let cd = grupos();
+        let mut rutas = vec![vec![1, 2, 3, 1], vec![1, 5, 4, 1]];
+        let movimiento = aplicar_vecindario(&mut rutas, &cd, 3, explorar_reubicacion);
+        assert!(matches!(movimiento, Movimiento::Reubicar { .. }));
+        assert_eq!(clientes_visitados(&rutas[..1]), vec![2, 3, 4]);
+    }
+
+    #[test]
+    fn intercambio_respeta_rutas_llenas() {
+        // Con capacidad 2 ningun cliente puede reubicarse
+        let cd = grupos();
+        let mut rutas = vec![vec![1, 2, 5, 1], vec![1, 6, 3, 1]];
+        let movimiento = aplicar_vecindario(&mut rutas, &cd, 2, |r, s, i, sel| {
+            explorar_cruzado(r, s, i, (1, 1), sel)
+        });
+        assert!(matches!(movimiento, Movimiento::Cruzado { .. }));
+        let mut grupos: Vec<Vec<NodoId>> = rutas
+            .iter()
+            .map(|ruta| clientes_visitados(std::slice::from_ref(ruta)))
+            .collect();
+        grupos.sort();
+        assert_eq!(grupos, vec![vec![2, 3], vec![5, 6]]);
+    }
+
+    #[test]
+    fn cruzado_intercambia_segmentos() {
+        let cd = grupos();
+        let mut rutas = vec![vec![1, 2, 5, 6, 1], vec![1, 7, 3, 4, 1]];
+        let movimiento = aplicar_vecindario(&mut rutas, &cd, 3, |r, s, i, sel| {
+            explorar_cruzado(r, s, i, (1, LONGITUD_CRUZADO), sel)
+        });
+        assert!(matches!(
+            movimiento,
+            Movimiento::Cruzado {
+                ia: 2,
+                ja: 3,
+                ib: 2,
+                jb: 3,
+                ..
+            }
+        ));
+        assert_eq!(rutas, vec![vec![1, 2, 3, 4, 1], vec![1, 7, 5, 6, 1]]);
+    }
+
+    #[test]
+    fn dos_opt_estrella_intercambia_colas() {
+        let cd = grupos();
+        let mut rutas = vec![vec![1, 2, 5, 1], vec![1, 6, 3, 1]];
+        let movimiento = aplicar_vecindario(&mut rutas, &cd, 2, explorar_dos_opt_estrella);
+        assert!(matches!(movimiento, Movimiento::DosOptEstrella { .. }));
+        assert_eq!(rutas.len(), 2);
+    }
+
+    #[test]
+    fn dos_opt_estrella_quita_la_ruta_vacia() {
+        let cd = grupos();
+        let mut rutas = vec![vec![1, 2, 1], vec![1, 3, 1]];
+        let movimiento = aplicar_vecindario(&mut rutas, &cd, 2, explorar_dos_opt_estrella);
+        assert!(matches!(movimiento, Movimiento::DosOptEstrella { .. }));
+        assert_eq!(rutas.len(), 1);
+        assert_eq!(clientes_visitados(&rutas), vec![2, 3]);
+    }
+
+    #[test]
+    fn mejorar_rutas_llega_a_un_optimo_local() {
+        // Clientes en una rejilla repartidos en rutas malas a proposito
+        let mut puntos = vec![(25.0, 25.0)];
+        for k in 0..24 {
+            puntos.push((
+                (k % 5) as f64 * 13.0,
+                (k / 5) as f64 * 11.0 + (k % 3) as f64,
+            ));
+        }
+        let cd = distancias(&puntos);
+        let n = cd.len() as u32;
+        let capacidad = 5;
+        let inicial: Vec<Camino> = (0..5)
+            .map(|r| {
+                let mut ruta = vec![DEPOSITO];
+                ruta.extend((2..=n).filter(|id| id % 5 == r));
+                ruta.push(DEPOSITO);
+                ruta
+            })
+            .collect();
+        let (clientes, demandas) = (clientes(&inicial), demandas(n));
+        let evaluar =
+            |rutas: &[Camino]| evaluar_rutas(rutas, &cd, capacidad, n, &clientes, DEPOSITO);
+
+        for criterio in [CriterioMejora::PrimeraMejora, CriterioMejora::MejorMejora] {
+            let mut rutas = inicial.clone();
+            mejorar_rutas(&mut rutas, &cd, &demandas, capacidad, criterio);
+            assert_eq!(clientes_visitados(&rutas), clientes_visitados(&inicial));
+            let evaluacion = evaluar(&rutas);
+            assert!(evaluacion.es_factible, "{:?}", evaluacion.violaciones);
+            assert!(evaluacion.costo_total < evaluar(&inicial).costo_total);
+
+            // Ninguna ruta tiene movimientos que mejoren
+            let inst = Instancia {
+                cd: &cd,
+                demandas: &demandas,
+                capacidad,
+            };
+            let solucion = Solucion::new(&mut rutas, &inst);
+            for r in 0..solucion.rutas.len() {
+                let mut seleccion = Seleccion {
+                    criterio: CriterioMejora::MejorMejora,
+                    elegido: None,
+                };
+                explorar_dos_opt(r, &solucion, &inst, &mut seleccion);
+                explorar_reubicacion(r, &solucion, &inst, &mut seleccion);
+                explorar_dos_opt_estrella(r, &solucion, &inst, &mut seleccion);
+                explorar_cruzado(r, &solucion, &inst, (1, LONGITUD_CRUZADO), &mut seleccion);
+                assert!(seleccion.elegido.is_none(), "{:?}", criterio);
+            }
+        }
+    }
+}
@@ -1,6 +1,12 @@
+mod ahorros;
+mod busqueda_local;
 mod rutas_vehiculares;
+mod tour_gigante;
 
+use crate::ahorros::*;
+use crate::busqueda_local::*;
 use crate::rutas_vehiculares::*;
+use crate::tour_gigante::*;
 
 use csv::Writer;
 use serde::Serialize;
@@ -10,56 +16,174 @@ use std::fs::File;
 use std::io::Write;
 use std::path::Path;
 
+// Las ejecuciones sin solucion quedan como celda vacia (NaN en pandas)
 #[derive(Serialize)]
 struct Fila {
     ejec: usize,
-    mas_importancia_distancia: usize,
-    solo_distancia: usize,
-    solo_feromona: usize,
-    mas_importancia_feromona: usize,
-    importancia_ambas: usize,
+    mas_importancia_distancia: Option<usize>,
+    solo_distancia: Option<usize>,
+    solo_feromona: Option<usize>,
+    mas_importancia_feromona: Option<usize>,
+    importancia_ambas: Option<usize>,
+    // Referencia determinista de Clarke y Wright, igual en todas las filas
+    ahorros_paralelo: Option<usize>,
+    ahorros_secuencial: Option<usize>,
 }
 
-fn rutas_vehiculares(
-    n_hormigas: usize,
-    rho: f64,
-    max_iteraciones: usize,
-    importancia_distancia: f64,
-    importancia_feromona: f64,
-    filepath: String,
-    test: bool,
-) -> Solucion {
+// Costo a escribir en el CSV; u32::MAX marca que no se encontro solucion
+fn costo_celda(costo: u32) -> Option<usize> {
+    (costo != u32::MAX).then_some(costo as usize)
+}
+
+fn rutas_vehiculares(configuracion: &Configuracion, filepath: String, test: bool) -> Solucion {
+    let n_hormigas = configuracion.n_hormigas;
+    let rho = configuracion.rho;
+    let max_iteraciones = configuracion.max_iteraciones;
+    let importancia_distancia = configuracion.importancia_distancia;
+    let importancia_feromona = configuracion.importancia_feromona;
+
     let datos = leer_matriz(filepath);
+    let n = datos.dimension as usize;
 
     // Inicializacion de conjunto de aristas, feromonas y distancias
-    let ca = create_conjunto_aristas(datos.dimension as usize);
+    let ca = create_conjunto_aristas(n);
     let cd = calcular_conjunto_distancias(&datos);
-    let mut cf = algoritmo_inicializacion(&ca);
+    let demandas = demandas_por_id(&datos.clientes);
+    let candidatos = listas_candidatos(
+        &cd,
+        &datos.clientes,
+        datos.deposito_id,
+        configuracion.n_candidatos,
+    );
+
+    // Soluciones de ahorros de Clarke y Wright como referencia
+    if !test {
+        for variante in [VarianteAhorros::Paralela, VarianteAhorros::Secuencial] {
+            let solucion = ahorros_clarke_wright(&datos, &cd, variante);
+            let evaluacion = evaluar_rutas(
+                &solucion.rutas,
+                &cd,
+                datos.capacidad,
+                datos.n_vehiculos,
+                &datos.clientes,
+                datos.deposito_id,
+            );
+            println!(
+                "Ahorros {:?}: costo {}, vehiculos {}{}",
+                variante,
+                solucion.costo_total,
+                solucion.vehiculos_usados,
+                match evaluacion.violaciones.first() {
+                    Some(violacion) => format!(" (infactible: {})", violacion),
+                    None => String::new(),
+                }
+            );
+        }
+    }
+    let semilla = configuracion
+        .semilla_ahorros
+        .map(|variante| ahorros_clarke_wright(&datos, &cd, variante));
+
+    // Limites de feromona estimados con el vecino mas cercano (o la semilla
+    // de ahorros) hasta tener una solucion factible
+    let costo_inicial = match &semilla {
+        Some(solucion) => solucion.costo_total,
+        None => costo_vecino_mas_cercano(&cd, &datos),
+    };
+    let mut limites = LimitesFeromona::new(costo_inicial, rho, n, configuracion.p_mejor);
+
+    // Rastros iniciales, tambien usados al reiniciar por estancamiento
+    let feromonas_iniciales = |limites: &LimitesFeromona| match (configuracion.modo, &semilla) {
+        (Modo::SistemaHormigas, Some(solucion)) => inicializacion_ahorros(&ca, solucion),
+        (Modo::SistemaHormigas, None) => algoritmo_inicializacion(&ca),
+        (Modo::MaxMin, _) => inicializacion_uniforme(&ca, limites.maximo),
+    };
+    let mut cf = feromonas_iniciales(&limites);
 
     let mut mejor_solucion = Solucion {
         rutas: vec![vec![]; n_hormigas],
         costo_total: u32::MAX,
         vehiculos_usados: 0,
     };
+    let mut iteraciones_sin_mejora = 0;
+
+    // ρ vigente: cambia si el estancamiento aumenta la evaporacion
+    let mut rho_actual = rho;
+    let criterio = configuracion.estancamiento;
+    let mut iteraciones_desde_accion = 0;
+    let mut acciones_estancamiento = 0;
 
     for i in 1..=max_iteraciones {
+        let diagnostico = diagnosticar(&ca, &cf, iteraciones_sin_mejora);
         if !test {
-            print!("\rProgreso: {}/{}", i, max_iteraciones);
+            print!(
+                "\rProgreso: {}/{} ({})   ",
+                i,
+                max_iteraciones,
+                diagnostico.resumen()
+            );
             std::io::Write::flush(&mut std::io::stdout()).unwrap();
         }
 
+        iteraciones_desde_accion += 1;
+        if criterio.estancado(&diagnostico, iteraciones_desde_accion) {
+            acciones_estancamiento += 1;
+            iteraciones_desde_accion = 0;
+            match criterio.accion {
+                AccionEstancamiento::Ninguna => {}
+                AccionEstancamiento::Reiniciar => cf = feromonas_iniciales(&limites),
+                AccionEstancamiento::AumentarEvaporacion => {
+                    rho_actual = aumentar_evaporacion(rho_actual)
+                }
+                AccionEstancamiento::Detener => {
+                    if !test {
+                        println!();
+                        println!("Colonia estancada: se detiene en la iteracion {}", i);
+                    }
+                    break;
+                }
+            }
+        }
+
         let mut hormigas = inicializacion_hormigas(n_hormigas);
 
-        construccion_rutas(
-            &ca,
-            &cf,
-            &cd,
+        match configuracion.construccion {
+            ModoConstruccion::RutasSecuenciales => construccion_rutas(
+                &ca,
+                &candidatos,
+                &cf,
+                &cd,
+                &mut hormigas,
+                datos.capacidad,
+                importancia_feromona,
+                importancia_distancia,
+                &configuracion.visibilidad,
+                datos.deposito_id,
+                &datos.clientes,
+            ),
+            ModoConstruccion::TourGigante => construccion_tour_gigante(
+                &candidatos,
+                &cf,
+                &cd,
+                &mut hormigas,
+                datos.capacidad,
+                datos.n_vehiculos,
+                importancia_feromona,
+                importancia_distancia,
+                &configuracion.visibilidad,
+                datos.deposito_id,
+                &datos.clientes,
+            ),
+        }
+
+        // Post-optimizacion de las rutas construidas
+        aplicar_busqueda_local(
             &mut hormigas,
+            &cd,
+            &demandas,
             datos.capacidad,
-            importancia_feromona,
-            importancia_distancia,
-            datos.deposito_id,
-            &datos.clientes,
+            configuracion.busqueda_local,
+            configuracion.criterio_mejora,
         );
 
         let evaluacion_rutas = evaluacion_soluciones(
@@ -68,41 +192,262 @@ fn rutas_vehiculares(
             datos.capacidad,
             datos.n_vehiculos,
             &datos.clientes,
+            datos.deposito_id,
         );
 
-        // TODO: Mejor ruta de la iteraccion actual
-        for i in 0..evaluacion_rutas.len() {
-            if evaluacion_rutas[i].costo_total < mejor_solucion.costo_total
-                && evaluacion_rutas[i].es_factible
-            {
-                mejor_solucion.rutas = hormigas[i].rutas.clone();
-                mejor_solucion.costo_total = evaluacion_rutas[i].costo_total;
-                mejor_solucion.vehiculos_usados = evaluacion_rutas[i].vehiculos_usados;
+        // Mejor ruta factible de la iteracion actual
+        let mejor_iteracion = evaluacion_rutas
+            .iter()
+            .enumerate()
+            .filter(|(_, evaluacion)| evaluacion.es_factible)
+            .min_by_key(|(_, evaluacion)| evaluacion.costo_total)
+            .map(|(indice, _)| indice);
+
+        match mejor_iteracion {
+            Some(indice) if evaluacion_rutas[indice].costo_total < mejor_solucion.costo_total => {
+                mejor_solucion.rutas = hormigas[indice].rutas.clone();
+                mejor_solucion.costo_total = evaluacion_rutas[indice].costo_total;
+                mejor_solucion.vehiculos_usados = evaluacion_rutas[indice].vehiculos_usados;
+                iteraciones_sin_mejora = 0;
+                rho_actual = rho;
+                limites =
+                    LimitesFeromona::new(mejor_solucion.costo_total, rho, n, configuracion.p_mejor);
             }
+            _ => iteraciones_sin_mejora += 1,
         }
 
-        evapozacion_feromona(&ca, &mut cf, rho);
+        match configuracion.modo {
+            Modo::SistemaHormigas => {
+                evapozacion_feromona(&ca, &mut cf, rho_actual, &limites.sin_maximo());
+
+                actualizacion_feromona_estrategia(
+                    configuracion,
+                    &hormigas,
+                    &mut cf,
+                    &evaluacion_rutas,
+                    &mejor_solucion,
+                );
+            }
+            Modo::MaxMin => {
+                evapozacion_feromona(&ca, &mut cf, rho_actual, &limites);
 
-        actualizacion_feromona(&hormigas, &mut cf, &evaluacion_rutas);
+                // Alternar entre la mejor de la iteracion y la mejor global
+                let usar_global = i % configuracion.frecuencia_mejor_global.max(1) == 0;
+                match mejor_iteracion {
+                    Some(indice) if !usar_global => actualizacion_feromona_mmas(
+                        &hormigas[indice].rutas,
+                        evaluacion_rutas[indice].costo_total,
+                        &mut cf,
+                        &limites,
+                    ),
+                    _ if mejor_solucion.costo_total != u32::MAX => actualizacion_feromona_mmas(
+                        &mejor_solucion.rutas,
+                        mejor_solucion.costo_total,
+                        &mut cf,
+                        &limites,
+                    ),
+                    _ => {}
+                }
+
+                // Estancamiento: reiniciar los rastros a τmax
+                if iteraciones_sin_mejora >= configuracion.iteraciones_reinicio {
+                    cf = inicializacion_uniforme(&ca, limites.maximo);
+                    iteraciones_sin_mejora = 0;
+                }
+            }
+        }
     }
 
     if !test {
         println!();
+        if criterio.accion != AccionEstancamiento::Ninguna {
+            println!(
+                "Acciones por estancamiento ({:?}): {}",
+                criterio.accion, acciones_estancamiento
+            );
+        }
+        if mejor_solucion.costo_total == u32::MAX {
+            println!("Ninguna hormiga construyo una solucion factible");
+            return mejor_solucion;
+        }
+        let evaluacion = evaluar_rutas(
+            &mejor_solucion.rutas,
+            &cd,
+            datos.capacidad,
+            datos.n_vehiculos,
+            &datos.clientes,
+            datos.deposito_id,
+        );
         println!("Mejor solución encontrada:");
         println!("Costo total: {}", mejor_solucion.costo_total);
         println!("Vehículos usados: {}", mejor_solucion.vehiculos_usados);
-        for (i, ruta) in mejor_solucion.rutas.iter().enumerate() {
-            println!("Ruta {}: {:?}", i + 1, ruta);
+        for (i, (ruta, carga)) in mejor_solucion
+            .rutas
+            .iter()
+            .zip(&evaluacion.cargas)
+            .enumerate()
+        {
+            println!(
+                "Ruta {}: {:?} (carga {}/{}, holgura {})",
+                i + 1,
+                ruta,
+                carga.carga,
+                datos.capacidad,
+                carga.holgura
+            );
+        }
+        for violacion in &evaluacion.violaciones {
+            println!("Violacion: {}", violacion);
         }
         println!();
     }
     mejor_solucion
 }
 
+// Argumentos despues de <filepath>: la palabra `test` y opciones --clave valor
+fn leer_opciones(opciones: &[String], configuracion: &mut Configuracion) -> Result<bool, String> {
+    let mut test = false;
+    let mut iter = opciones.iter();
+    while let Some(clave) = iter.next() {
+        if clave == "test" {
+            test = true;
+            continue;
+        }
+        let valor = iter
+            .next()
+            .ok_or(format!("Falta el valor de la opcion {}", clave))?;
+        let numero = || {
+            valor
+                .parse::<f64>()
+                .map_err(|_| format!("Valor invalido para {}: {}", clave, valor))
+        };
+        let entero = || {
+            valor
+                .parse::<usize>()
+                .map_err(|_| format!("Valor invalido para {}: {}", clave, valor))
+        };
+        match clave.as_str() {
+            "--modo" => {
+                configuracion.modo = match valor.as_str() {
+                    "as" => Modo::SistemaHormigas,
+                    "mmas" => Modo::MaxMin,
+                    _ => return Err(format!("Modo desconocido: {} (as | mmas)", valor)),
+                }
+            }
+            "--actualizacion" => {
+                configuracion.estrategia = match valor.as_str() {
+                    "todas" => EstrategiaActualizacion::TodasLasHormigas,
+                    "elitista" => EstrategiaActualizacion::Elitista,
+                    "rango" => EstrategiaActualizacion::Rango,
+                    "mejor-iteracion" => EstrategiaActualizacion::MejorIteracion,
+                    _ => {
+                        return Err(format!(
+                    "Actualizacion desconocida: {} (todas | elitista | rango | mejor-iteracion)",
+                    valor
+                ))
+                    }
+                }
+            }
+            "--peso-elitista" => configuracion.peso_elitista = numero()?,
+            "--w-rango" => configuracion.hormigas_rango = entero()?,
+            "--p-mejor" => configuracion.p_mejor = numero()?,
+            "--frecuencia-global" => configuracion.frecuencia_mejor_global = entero()?,
+            "--reinicio" => configuracion.iteraciones_reinicio = entero()?,
+            "--candidatos" => configuracion.n_candidatos = entero()?,
+            "--ahorros" => {
+                configuracion.semilla_ahorros = match valor.as_str() {
+                    "ninguno" => None,
+                    "paralelo" => Some(VarianteAhorros::Paralela),
+                    "secuencial" => Some(VarianteAhorros::Secuencial),
+                    _ => {
+                        return Err(format!(
+                            "Variante de ahorros desconocida: {} (ninguno | paralelo | secuencial)",
+                            valor
+                        ))
+                    }
+                }
+            }
+            "--construccion" => {
+                configuracion.construccion = match valor.as_str() {
+                    "rutas" => ModoConstruccion::RutasSecuenciales,
+                    "split" => ModoConstruccion::TourGigante,
+                    _ => {
+                        return Err(format!(
+                            "Construccion desconocida: {} (rutas | split)",
+                            valor
+                        ))
+                    }
+                }
+            }
+            "--visibilidad" => {
+                configuracion.visibilidad.modelo = match valor.as_str() {
+                    "distancia" => ModeloVisibilidad::Distancia,
+                    "ahorros" => ModeloVisibilidad::Ahorros,
+                    _ => {
+                        return Err(format!(
+                            "Visibilidad desconocida: {} (distancia | ahorros)",
+                            valor
+                        ))
+                    }
+                }
+            }
+            "--importancia-ahorros" => configuracion.visibilidad.importancia_ahorros = numero()?,
+            "--importancia-capacidad" => {
+                configuracion.visibilidad.importancia_capacidad = numero()?
+            }
+            "--busqueda-local" => {
+                configuracion.busqueda_local = match valor.as_str() {
+                    "ninguna" => BusquedaLocal::Ninguna,
+                    "todas" => BusquedaLocal::TodasLasHormigas,
+                    "mejor" => BusquedaLocal::MejorHormiga,
+                    _ => {
+                        return Err(format!(
+                            "Busqueda local desconocida: {} (ninguna | todas | mejor)",
+                            valor
+                        ))
+                    }
+                }
+            }
+            "--mejora" => {
+                configuracion.criterio_mejora = match valor.as_str() {
+                    "primera" => CriterioMejora::PrimeraMejora,
+                    "mejor" => CriterioMejora::MejorMejora,
+                    _ => return Err(format!("Criterio de mejora desconocido: {} (primera | mejor)", valor)),
+                }
+            }
+            "--estancamiento" => {
+                configuracion.estancamiento.accion = match valor.as_str() {
+                    "ninguna" => AccionEstancamiento::Ninguna,
+                    "reiniciar" => AccionEstancamiento::Reiniciar,
+                    "detener" => AccionEstancamiento::Detener,
+                    "evaporar" => AccionEstancamiento::AumentarEvaporacion,
+                    _ => {
+                        return Err(format!(
+                            "Accion de estancamiento desconocida: {} (ninguna | reiniciar | detener | evaporar)",
+                            valor
+                        ))
+                    }
+                }
+            }
+            "--sin-mejora" => configuracion.estancamiento.iteraciones_sin_mejora = entero()?,
+            "--umbral-ramificacion" => {
+                configuracion.estancamiento.umbral_ramificacion = numero()?
+            }
+            "--umbral-entropia" => configuracion.estancamiento.umbral_entropia = numero()?,
+            _ => return Err(format!("Opcion desconocida: {}", clave)),
+        }
+    }
+
+    if !(configuracion.p_mejor > 0.0 && configuracion.p_mejor < 1.0) {
+        return Err("p-mejor debe estar en (0, 1)".into());
+    }
+    Ok(test)
+}
+
 fn main() {
     let args: Vec<String> = env::args().collect();
     if args.len() < 7 {
-        eprintln!("Uso: {} <n_hormigas> <rho> <max_iteraciones> <importancia_distancia> <importancia_feromona> <filepath> <test>", args[0]);
+        eprintln!("Uso: {} <n_hormigas> <rho> <max_iteraciones> <importancia_distancia> <importancia_feromona> <filepath> [test] [--modo as|mmas] [--actualizacion todas|elitista|rango|mejor-iteracion] [--peso-elitista e] [--w-rango w] [--p-mejor p] [--frecuencia-global k] [--reinicio n] [--candidatos k] [--ahorros ninguno|paralelo|secuencial] [--construccion rutas|split] [--visibilidad distancia|ahorros] [--importancia-ahorros l] [--importancia-capacidad k] [--busqueda-local ninguna|todas|mejor] [--mejora primera|mejor] [--estancamiento ninguna|reiniciar|detener|evaporar] [--sin-mejora n] [--umbral-ramificacion b] [--umbral-entropia h]", args[0]);
         return;
     }
     let n_hormigas: usize = args[1]
@@ -119,7 +464,22 @@ fn main() {
         .parse()
         .expect("Error al parsear la importancia de la feromona");
     let filepath = args[6].clone();
-    let test = args.get(7).map_or(false, |s| s == "test");
+
+    let mut configuracion = Configuracion {
+        n_hormigas,
+        rho,
+        max_iteraciones,
+        importancia_distancia,
+        importancia_feromona,
+        ..Configuracion::default()
+    };
+    let test = match leer_opciones(&args[7..], &mut configuracion) {
+        Ok(test) => test,
+        Err(e) => {
+            eprintln!("{}", e);
+            return;
+        }
+    };
 
     println!("Ejecutando algoritmo con los siguientes parámetros:");
     println!("Número de hormigas: {}", n_hormigas);
@@ -127,6 +487,35 @@ fn main() {
     println!("Máximo de iteraciones: {}", max_iteraciones);
     println!("Importancia de la distancia: {}", importancia_distancia);
     println!("Importancia de la feromona: {}", importancia_feromona);
+    println!("Modo: {:?}", configuracion.modo);
+    println!("Listas de candidatos: {}", configuracion.n_candidatos);
+    println!("Construccion: {:?}", configuracion.construccion);
+    if configuracion.busqueda_local != BusquedaLocal::Ninguna {
+        println!(
+            "Busqueda local: {:?} ({:?})",
+            configuracion.busqueda_local, configuracion.criterio_mejora
+        );
+    }
+    if configuracion.modo == Modo::SistemaHormigas {
+        println!("Actualizacion: {:?}", configuracion.estrategia);
+    }
+    if configuracion.modo == Modo::MaxMin {
+        println!(
+            "p_mejor: {}, frecuencia mejor global: {}, reinicio: {} iteraciones",
+            configuracion.p_mejor,
+            configuracion.frecuencia_mejor_global,
+            configuracion.iteraciones_reinicio
+        );
+    }
+    if configuracion.estancamiento.accion != AccionEstancamiento::Ninguna {
+        println!("Estancamiento: {:?}", configuracion.estancamiento);
+    }
+    if configuracion.visibilidad.modelo != ModeloVisibilidad::Distancia {
+        println!("Visibilidad: {:?}", configuracion.visibilidad);
+    }
+    if let Some(variante) = configuracion.semilla_ahorros {
+        println!("Feromona inicial: ahorros {:?}", variante);
+    }
     println!("Archivo de datos: {}", filepath);
     println!();
 
@@ -139,15 +528,26 @@ fn main() {
             .and_then(|s| s.to_str())
             .unwrap_or("output");
 
-        let csv_path = format!("{}/{}.csv", dir, file_stem);
+        // Resultados separados por modelo de visibilidad para compararlos
+        let sufijo = match configuracion.visibilidad.modelo {
+            ModeloVisibilidad::Distancia => "",
+            ModeloVisibilidad::Ahorros => "_ahorros",
+        };
+        let csv_path = format!("{}/{}{}.csv", dir, file_stem, sufijo);
         println!("Guardando resultados en: {}", csv_path);
 
         let mut writer = Writer::from_path(&csv_path).expect("No se pudo crear el archivo CSV");
 
+        let datos = leer_matriz(filepath.clone());
+        let cd = calcular_conjunto_distancias(&datos);
+        let [ahorros_paralelo, ahorros_secuencial] =
+            [VarianteAhorros::Paralela, VarianteAhorros::Secuencial]
+                .map(|variante| ahorros_clarke_wright(&datos, &cd, variante).costo_total);
+
         let mut mejores: Vec<Option<(usize, Vec<Vec<u32>>)>> = vec![None; 5];
 
         for i in 0..30 {
-            let mut resultados = vec![0; 5];
+            let mut resultados = [0; 5];
             for j in 0..=4 {
                 print!(
                     "\rEjecucion {}: {}/30, Prueba de parametros: {}/5",
@@ -176,39 +576,39 @@ fn main() {
                     i_f = 1.0;
                 }
                 // Devolver costo y solución completa
-                let sol = rutas_vehiculares(
-                    n_hormigas,
-                    rho,
-                    max_iteraciones,
-                    i_d,
-                    i_f,
-                    filepath.clone(),
-                    test,
-                );
+                let configuracion_prueba = Configuracion {
+                    importancia_distancia: i_d,
+                    importancia_feromona: i_f,
+                    ..configuracion.clone()
+                };
+                let sol = rutas_vehiculares(&configuracion_prueba, filepath.clone(), test);
                 resultados[j] = sol.costo_total;
                 // Actualizar mejor solución si es primera vez o encontramos menor costo
                 let rutas = sol.rutas;
-                if mejores[j]
-                    .as_ref()
-                    .map_or(true, |m| sol.costo_total < m.0 as u32)
+                if sol.costo_total != u32::MAX
+                    && mejores[j]
+                        .as_ref()
+                        .is_none_or(|m| sol.costo_total < m.0 as u32)
                 {
                     mejores[j] = Some((sol.costo_total as usize, rutas.clone()));
                 }
             }
             let fila = Fila {
                 ejec: i + 1,
-                mas_importancia_distancia: resultados[0] as usize,
-                solo_distancia: resultados[1] as usize,
-                solo_feromona: resultados[2] as usize,
-                mas_importancia_feromona: resultados[3] as usize,
-                importancia_ambas: resultados[4] as usize,
+                mas_importancia_distancia: costo_celda(resultados[0]),
+                solo_distancia: costo_celda(resultados[1]),
+                solo_feromona: costo_celda(resultados[2]),
+                mas_importancia_feromona: costo_celda(resultados[3]),
+                importancia_ambas: costo_celda(resultados[4]),
+                ahorros_paralelo: costo_celda(ahorros_paralelo),
+                ahorros_secuencial: costo_celda(ahorros_secuencial),
             };
             writer.serialize(fila).expect("No se pudo escribir la fila");
         }
         println!();
         writer.flush().expect("No se pudo guardar el archivo CSV");
         // Mejores soluciones a un archivo de texto
-        let txt_path = format!("{}/solutions_{}.txt", dir, file_stem);
+        let txt_path = format!("{}/solutions_{}{}.txt", dir, file_stem, sufijo);
         let mut f = File::create(&txt_path).expect("No se pudo crear el archivo de soluciones");
         for (idx, opt) in mejores.into_iter().enumerate() {
             if let Some((cost, rutas)) = opt {
@@ -224,18 +624,40 @@ fn main() {
         }
         println!("Soluciones guardadas en {}", txt_path);
     } else {
-        let sol = rutas_vehiculares(
-            n_hormigas,
-            rho,
-            max_iteraciones,
-            importancia_distancia,
-            importancia_feromona,
-            filepath.clone(),
-            test,
-        );
+        let sol = rutas_vehiculares(&configuracion, filepath.clone(), test);
         if let Some(valor_optimo) = leer_valor_optimo(&filepath) {
             println!("Valor óptimo conocido: {}", valor_optimo);
         }
-        println!("Costo de la mejor solución: {}", sol.costo_total);
+        if sol.costo_total != u32::MAX {
+            println!("Costo de la mejor solución: {}", sol.costo_total);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ejecucion_sin_solucion_deja_celda_vacia() {
+        let fila = Fila {
+            ejec: 1,
+            mas_importancia_distancia: costo_celda(784),
+            solo_distancia: costo_celda(u32::MAX),
+            solo_feromona: costo_celda(0),
+            mas_importancia_feromona: costo_celda(u32::MAX),
+            importancia_ambas: costo_celda(801),
+            ahorros_paralelo: costo_celda(790),
+            ahorros_secuencial: costo_celda(u32::MAX),
+        };
+
+        let mut writer = Writer::from_writer(Vec::new());
+        writer.serialize(fila).unwrap();
+        let csv = String::from_utf8(writer.into_inner().unwrap()).unwrap();
+
+        let lineas: Vec<&str> = csv.lines().collect();
+        assert_eq!(lineas.len(), 2);
+        assert_eq!(lineas[1], "1,784,,0,,801,790,");
+        assert!(!csv.contains(&u32::MAX.to_string()));
     }
 }
@@ -1,5 +1,12 @@
 #![allow(dead_code)]
 
+use crate::ahorros::VarianteAhorros;
+use crate::busqueda_local::{BusquedaLocal, CriterioMejora};
+use crate::tour_gigante::ModoConstruccion;
+pub use colonia::estancamiento::*;
+use colonia::k_mas_cercanos;
+pub use colonia::LimitesFeromona;
+pub use colonia::{ListasCandidatos, Visitados};
 use rand::prelude::*;
 
 pub type NodoId = u32;
@@ -15,10 +22,250 @@ pub type Evaluaciones = Vec<EvaluacionSolucion>;
 pub type ImportanciaDistancia = f64;
 pub type ImportanciaFeromona = f64;
 
+// Variante del algoritmo que se ejecuta en el ciclo principal
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Modo {
+    // Ant System: todas las hormigas factibles depositan
+    SistemaHormigas,
+    // MAX-MIN Ant System: solo deposita la mejor hormiga, feromona acotada
+    // en [τmin, τmax] y reinicio de rastros al estancarse
+    MaxMin,
+}
+
+// Que hormigas depositan feromona en Ant System y con que peso
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EstrategiaActualizacion {
+    // Todas las hormigas factibles depositan 1 / L_k con el mismo peso
+    TodasLasHormigas,
+    // Todas depositan y ademas la mejor global deposita e / L_mejor
+    Elitista,
+    // Las w - 1 mejores de la iteracion depositan (w - r) / L_r y la mejor
+    // global deposita w / L_mejor
+    Rango,
+    // Solo deposita la mejor hormiga factible de la iteracion
+    MejorIteracion,
+}
+
+// Informacion heuristica η usada al elegir el siguiente cliente
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ModeloVisibilidad {
+    // Solo η_ij = 1 / d_ij
+    Distancia,
+    // Ant System mejorado de Bullnheimer, Hartl y Strauss: ademas de
+    // (1 / d_ij)^β se multiplica por el ahorro s_ij = d_i0 + d_0j - d_ij y por
+    // la utilizacion κ_ij = (Q_i + q_j) / Q del vehiculo tras visitar j
+    Ahorros,
+}
+
+// Ahorro minimo en el modelo de ahorros: desde el deposito todos los
+// ahorros son 0 y un ahorro negativo no debe anular la probabilidad
+const AHORRO_MINIMO: Distancia = 1.0;
+
+#[derive(Debug, Clone, Copy)]
+pub struct Visibilidad {
+    pub modelo: ModeloVisibilidad,
+    // Exponente λ del ahorro
+    pub importancia_ahorros: f64,
+    // Exponente κ de la utilizacion de capacidad
+    pub importancia_capacidad: f64,
+}
+
+impl Default for Visibilidad {
+    fn default() -> Visibilidad {
+        Visibilidad {
+            modelo: ModeloVisibilidad::Distancia,
+            importancia_ahorros: 1.0,
+            importancia_capacidad: 1.0,
+        }
+    }
+}
+
+impl Visibilidad {
+    // Factor s_ij^λ * κ_ij^κ que acompaña a τ_ij^α * η_ij^β; 1 con el
+    // modelo de distancia. `utilizacion` es κ_ij, la fraccion de la
+    // capacidad ocupada si el vehiculo visita j.
+    pub fn termino_adicional(
+        &self,
+        cd: &ConjuntoDistancias,
+        deposito: NodoId,
+        origen: NodoId,
+        destino: NodoId,
+        utilizacion: f64,
+    ) -> Feromona {
+        match self.modelo {
+            ModeloVisibilidad::Distancia => 1.0,
+            ModeloVisibilidad::Ahorros => {
+                let (i, j, d) = (
+                    (origen - 1) as usize,
+                    (destino - 1) as usize,
+                    (deposito - 1) as usize,
+                );
+                let ahorro = (cd[i][d] + cd[d][j] - cd[i][j]).max(AHORRO_MINIMO);
+                ahorro.powf(self.importancia_ahorros) * utilizacion.powf(self.importancia_capacidad)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Configuracion {
+    pub n_hormigas: usize,
+    pub rho: Rho,
+    pub max_iteraciones: usize,
+    pub importancia_distancia: ImportanciaDistancia,
+    pub importancia_feromona: ImportanciaFeromona,
+    pub modo: Modo,
+    // Regla de deposito en Ant System
+    pub estrategia: EstrategiaActualizacion,
+    // Peso e de la mejor global en la estrategia elitista
+    pub peso_elitista: f64,
+    // Numero w de hormigas que depositan en la estrategia por rango
+    pub hormigas_rango: usize,
+    // Probabilidad de construir la mejor solucion al converger (MMAS)
+    pub p_mejor: f64,
+    // Cada cuantas iteraciones deposita la mejor global en vez de la mejor
+    // de la iteracion (MMAS)
+    pub frecuencia_mejor_global: usize,
+    // Iteraciones sin mejora antes de reiniciar los rastros (MMAS)
+    pub iteraciones_reinicio: usize,
+    // Tamaño k de las listas de candidatos (0 = todos los clientes)
+    pub n_candidatos: usize,
+    // Cuando se considera estancada la colonia y que hacer entonces
+    pub estancamiento: CriterioEstancamiento,
+    // Variante de ahorros cuya solucion siembra los rastros iniciales de AS
+    // y da la cota inicial de los limites de MMAS (None = feromona aleatoria
+    // y limites del vecino mas cercano)
+    pub semilla_ahorros: Option<VarianteAhorros>,
+    // Rutas una a una o tour gigante partido con Split
+    pub construccion: ModoConstruccion,
+    // Informacion heuristica de la construccion de rutas
+    pub visibilidad: Visibilidad,
+    // Hormigas cuyas rutas se mejoran despues de construirlas
+    pub busqueda_local: BusquedaLocal,
+    pub criterio_mejora: CriterioMejora,
+}
+
+impl Default for Configuracion {
+    fn default() -> Configuracion {
+        Configuracion {
+            n_hormigas: 10,
+            rho: 0.1,
+            max_iteraciones: 100,
+            importancia_distancia: 2.0,
+            importancia_feromona: 1.0,
+            modo: Modo::SistemaHormigas,
+            estrategia: EstrategiaActualizacion::TodasLasHormigas,
+            peso_elitista: 1.0,
+            hormigas_rango: 6,
+            p_mejor: 0.05,
+            frecuencia_mejor_global: 5,
+            iteraciones_reinicio: 100,
+            n_candidatos: 20,
+            estancamiento: CriterioEstancamiento::default(),
+            semilla_ahorros: None,
+            construccion: ModoConstruccion::RutasSecuenciales,
+            visibilidad: Visibilidad::default(),
+            busqueda_local: BusquedaLocal::Ninguna,
+            criterio_mejora: CriterioMejora::PrimeraMejora,
+        }
+    }
+}
+
 pub struct EvaluacionSolucion {
     pub costo_total: u32,
     pub vehiculos_usados: u32,
+    // true solo si no hay ninguna violacion
     pub es_factible: bool,
+    // Carga y holgura de cada ruta, en el orden de `rutas`
+    pub cargas: Vec<CargaRuta>,
+    pub violaciones: Vec<Violacion>,
+}
+
+// Carga de una ruta frente a la capacidad del vehiculo
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CargaRuta {
+    pub carga: u32,
+    // Capacidad sin usar; negativa si la ruta excede la capacidad
+    pub holgura: i64,
+}
+
+// Motivos por los que una solucion no es factible. Las rutas se numeran
+// desde 1, como al imprimirlas.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Violacion {
+    Capacidad {
+        ruta: usize,
+        carga: u32,
+        capacidad: u32,
+    },
+    // La ruta no empieza o no termina en el deposito
+    RutaSinDeposito {
+        ruta: usize,
+    },
+    // Un nodo dentro de la ruta que no es cliente (p. ej. el deposito)
+    NodoNoCliente {
+        ruta: usize,
+        nodo: NodoId,
+    },
+    // Un id que no esta en la instancia (0 o mayor que la dimension)
+    NodoInvalido {
+        ruta: usize,
+        nodo: NodoId,
+    },
+    ClienteRepetido {
+        cliente: NodoId,
+        visitas: u32,
+    },
+    ClienteNoVisitado {
+        cliente: NodoId,
+    },
+    DemasiadosVehiculos {
+        usados: u32,
+        disponibles: u32,
+    },
+}
+
+impl std::fmt::Display for Violacion {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Violacion::Capacidad {
+                ruta,
+                carga,
+                capacidad,
+            } => write!(
+                f,
+                "Ruta {}: carga {} > capacidad {}",
+                ruta, carga, capacidad
+            ),
+            Violacion::RutaSinDeposito { ruta } => {
+                write!(f, "Ruta {}: no empieza y termina en el deposito", ruta)
+            }
+            Violacion::NodoNoCliente { ruta, nodo } => {
+                write!(f, "Ruta {}: el nodo {} no es un cliente", ruta, nodo)
+            }
+            Violacion::NodoInvalido { ruta, nodo } => {
+                write!(
+                    f,
+                    "Ruta {}: el nodo {} no existe en la instancia",
+                    ruta, nodo
+                )
+            }
+            Violacion::ClienteRepetido { cliente, visitas } => {
+                write!(f, "Cliente {} visitado {} veces", cliente, visitas)
+            }
+            Violacion::ClienteNoVisitado { cliente } => {
+                write!(f, "Cliente {} sin visitar", cliente)
+            }
+            Violacion::DemasiadosVehiculos {
+                usados,
+                disponibles,
+            } => write!(
+                f,
+                "{} vehiculos usados de {} disponibles",
+                usados, disponibles
+            ),
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -90,6 +337,54 @@ pub fn algoritmo_inicializacion(ca: &ConjuntoAristas) -> ConjuntoFeromonas {
     cf
 }
 
+pub fn inicializacion_uniforme(ca: &ConjuntoAristas, feromona: Feromona) -> ConjuntoFeromonas {
+    // Mismo valor de feromona en todas las aristas validas (τmax en MMAS)
+    ca.iter()
+        .enumerate()
+        .map(|(i, fila)| {
+            fila.iter()
+                .enumerate()
+                .map(|(j, &arista)| if i != j && arista != 0 { feromona } else { 0.0 })
+                .collect()
+        })
+        .collect()
+}
+
+pub fn costo_vecino_mas_cercano(cd: &ConjuntoDistancias, datos: &DatosVRP) -> u32 {
+    // Solucion golosa: ir al cliente mas cercano que cabe en el vehiculo,
+    // regresar al deposito cuando ninguno cabe
+    let deposito = datos.deposito_id;
+    let mut sin_visitar: Vec<&Cliente> =
+        datos.clientes.iter().filter(|c| c.id != deposito).collect();
+    let mut costo = 0.0;
+
+    while !sin_visitar.is_empty() {
+        let mut actual = deposito;
+        let mut capacidad_restante = datos.capacidad;
+        while let Some(posicion) = sin_visitar
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| c.demanda <= capacidad_restante)
+            .min_by(|(_, a), (_, b)| {
+                cd[(actual - 1) as usize][(a.id - 1) as usize]
+                    .total_cmp(&cd[(actual - 1) as usize][(b.id - 1) as usize])
+            })
+            .map(|(posicion, _)| posicion)
+        {
+            let cliente = sin_visitar.remove(posicion);
+            costo += cd[(actual - 1) as usize][(cliente.id - 1) as usize];
+            capacidad_restante -= cliente.demanda;
+            actual = cliente.id;
+        }
+        if actual == deposito {
+            // Ningun cliente cabe en un vehiculo vacio
+            break;
+        }
+        costo += cd[(actual - 1) as usize][(deposito - 1) as usize];
+    }
+    costo as u32
+}
+
 pub fn inicializacion_hormigas(n_hormigas: usize) -> Hormigas {
     let mut h: Hormigas = Vec::with_capacity(n_hormigas);
     for _ in 0..n_hormigas {
@@ -98,31 +393,54 @@ pub fn inicializacion_hormigas(n_hormigas: usize) -> Hormigas {
     h
 }
 
+pub fn listas_candidatos(
+    cd: &ConjuntoDistancias,
+    clientes: &[Cliente],
+    deposito: NodoId,
+    k: usize,
+) -> ListasCandidatos {
+    // Los k clientes mas cercanos a cada nodo (deposito incluido), fijos
+    // durante toda la ejecucion. Con k = 0 se usan todos los clientes.
+    let vecindades = (0..cd.len())
+        .map(|i| {
+            clientes
+                .iter()
+                .map(|c| c.id)
+                .filter(|&id| id != deposito && (id - 1) as usize != i)
+                .collect()
+        })
+        .collect();
+    k_mas_cercanos(vecindades, |i, id| cd[i][(id - 1) as usize], k)
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn construccion_rutas(
     _ca: &ConjuntoAristas,
+    candidatos: &ListasCandidatos,
     cf: &ConjuntoFeromonas,
     cd: &ConjuntoDistancias,
     h: &mut Hormigas,
     capacidad_maxima: u32,
     i_f: ImportanciaFeromona,
     i_d: ImportanciaDistancia,
+    visibilidad: &Visibilidad,
     deposito: NodoId,
-    clientes: &Vec<Cliente>,
+    clientes: &[Cliente],
 ) {
+    let demandas = demandas_por_id(clientes);
+
     // Para cada hormiga
     for hormiga in h.iter_mut() {
         // Limpiar rutas previas
         hormiga.rutas.clear();
 
-        // Crear conjunto de clientes sin visitar (excluir depósito)
-        let mut clientes_sin_visitar: Vec<NodoId> = clientes
-            .iter()
-            .filter(|d| d.id != deposito)
-            .map(|d| d.id)
-            .collect();
+        // Clientes sin visitar (excluir depósito)
+        let mut visitados = Visitados::new(demandas.len());
+        visitados.insertar(deposito);
+        let mut clientes_sin_visitar = clientes.iter().filter(|c| c.id != deposito).count();
 
         // Construir rutas hasta visitar todos los clientes
-        while !clientes_sin_visitar.is_empty() {
+        while clientes_sin_visitar > 0 {
             // Crear nueva ruta empezando desde el depósito
             let mut ruta_actual = vec![deposito];
             let mut capacidad_restante = capacidad_maxima;
@@ -130,22 +448,26 @@ pub fn construccion_rutas(
 
             // Construir ruta para el vehículo actual
             loop {
-                // Filtrar vértices factibles por capacidad
-                let vertices_factibles: Vec<NodoId> = clientes_sin_visitar
+                // Un cliente es factible si no se ha visitado y su demanda
+                // no excede la capacidad restante
+                let factible = |id: NodoId| {
+                    !visitados.contiene(id) && demandas[id as usize] <= capacidad_restante
+                };
+
+                // Primero los candidatos del nodo actual; si ninguno es
+                // factible, todos los clientes
+                let mut vertices_factibles: Vec<NodoId> = candidatos[(nodo_actual - 1) as usize]
                     .iter()
-                    .filter(|&&cliente| {
-                        // Encontrar la demanda del cliente
-                        let demanda = clientes
-                            .iter()
-                            .find(|d| d.id == cliente)
-                            .map(|d| d.demanda)
-                            .unwrap_or(0);
-
-                        // Verificar que la demanda no exceda la capacidad restante
-                        demanda <= capacidad_restante
-                    })
                     .copied()
+                    .filter(|&id| factible(id))
                     .collect();
+                if vertices_factibles.is_empty() {
+                    vertices_factibles = clientes
+                        .iter()
+                        .map(|c| c.id)
+                        .filter(|&id| factible(id))
+                        .collect();
+                }
 
                 // Si no hay vértices factibles, terminar la ruta del vehículo actual
                 if vertices_factibles.is_empty() {
@@ -153,23 +475,31 @@ pub fn construccion_rutas(
                 }
 
                 // Seleccionar siguiente nodo usando ruleta
-                if let Some(siguiente_nodo) =
-                    seleccion_ruleta(nodo_actual, &vertices_factibles, cf, cd, i_d, i_f)
-                {
+                let carga = capacidad_maxima - capacidad_restante;
+                let termino_adicional = |destino: NodoId| {
+                    let utilizacion = (carga + demandas[destino as usize]) as f64
+                        / capacidad_maxima.max(1) as f64;
+                    visibilidad.termino_adicional(cd, deposito, nodo_actual, destino, utilizacion)
+                };
+                if let Some(siguiente_nodo) = seleccion_ruleta(
+                    nodo_actual,
+                    &vertices_factibles,
+                    cf,
+                    cd,
+                    i_d,
+                    i_f,
+                    termino_adicional,
+                ) {
                     // Agregar el nodo a la ruta
                     ruta_actual.push(siguiente_nodo);
 
                     // Actualizar capacidad restante
-                    let demanda_cliente = clientes
-                        .iter()
-                        .find(|d| d.id == siguiente_nodo)
-                        .map(|d| d.demanda)
-                        .unwrap_or(0);
+                    capacidad_restante =
+                        capacidad_restante.saturating_sub(demandas[siguiente_nodo as usize]);
 
-                    capacidad_restante = capacidad_restante.saturating_sub(demanda_cliente);
-
-                    // Remover cliente de la lista de sin visitar
-                    clientes_sin_visitar.retain(|&x| x != siguiente_nodo);
+                    // Marcar el cliente como visitado
+                    visitados.insertar(siguiente_nodo);
+                    clientes_sin_visitar -= 1;
 
                     // Actualizar nodo actual
                     nodo_actual = siguiente_nodo;
@@ -179,6 +509,11 @@ pub fn construccion_rutas(
                 }
             }
 
+            // Ningun cliente restante cabe en un vehiculo vacio
+            if ruta_actual.len() == 1 {
+                break;
+            }
+
             // Siempre regresar al deposito al finalizar la ruta
             ruta_actual.push(deposito);
 
@@ -188,18 +523,15 @@ pub fn construccion_rutas(
     }
 }
 
-fn seleccion_ruleta(
+pub fn seleccion_ruleta(
     origen: NodoId,
     vertices_factibles: &[NodoId],
     cf: &ConjuntoFeromonas,
     cd: &ConjuntoDistancias,
     i_d: ImportanciaDistancia,
     i_f: ImportanciaFeromona,
+    termino_adicional: impl Fn(NodoId) -> Feromona,
 ) -> Option<NodoId> {
-    if vertices_factibles.is_empty() {
-        return None;
-    }
-
     if vertices_factibles.len() == 1 {
         return vertices_factibles.first().copied();
     }
@@ -207,70 +539,66 @@ fn seleccion_ruleta(
     let origen_indice = (origen - 1) as usize;
 
     let mut rng = rand::rng();
-    let umbral: f64 = rng.random_range(0.0..=1.0);
-
-    let mut proporcion = 0.0;
-
-    let mut valores_probabilidad: Vec<(NodoId, f64)> = Vec::new();
-    let mut suma_total = 0.0;
 
-    for &destino in vertices_factibles {
-        let destino_indice = (destino - 1) as usize;
+    let pesos: Vec<Feromona> = vertices_factibles
+        .iter()
+        .map(|&destino| {
+            let destino_indice = (destino - 1) as usize;
 
-        let feromona_ij = cf[origen_indice][destino_indice];
-        let distancia_ij = cd[origen_indice][destino_indice];
+            let feromona_ij = cf[origen_indice][destino_indice];
+            let distancia_ij = cd[origen_indice][destino_indice];
 
-        if distancia_ij <= 0.0 {
-            // Si la distancia es 0, este nodo tiene probabilidad máxima
-            return Some(destino);
-        }
-
-        // Mayor preferencia a distancias cortas
-        let visibilidad_ij = 1.0 / distancia_ij as f64;
-
-        // Aplicar las importancias (exponentes): τ_ij^α * η_ij^β
-        let valor = feromona_ij.powf(i_f) * visibilidad_ij.powf(i_d);
-
-        valores_probabilidad.push((destino, valor));
-        suma_total += valor;
-    }
-
-    let mut vertices_disponibles = valores_probabilidad;
-    let mut nodo_j = None;
+            // Mayor preferencia a distancias cortas; distancia 0 da
+            // visibilidad infinita (probabilidad maxima)
+            let visibilidad_ij = 1.0 / distancia_ij.max(0.0);
 
-    while proporcion < umbral && !vertices_disponibles.is_empty() {
-        let indice_aleatorio = rng.random_range(0..vertices_disponibles.len());
-        let (j_seleccionado, valor_j) = vertices_disponibles[indice_aleatorio];
-        nodo_j = Some(j_seleccionado);
+            // Aplicar las importancias (exponentes): τ_ij^α * η_ij^β, por el
+            // termino de ahorros y capacidad si el modelo lo incluye
+            feromona_ij.powf(i_f) * visibilidad_ij.powf(i_d) * termino_adicional(destino)
+        })
+        .collect();
 
-        proporcion += valor_j / suma_total;
-
-        vertices_disponibles.remove(indice_aleatorio);
-    }
-
-    nodo_j
+    ruleta::seleccionar(&pesos, &mut rng).map(|indice| vertices_factibles[indice])
 }
 
-pub fn evapozacion_feromona(_ca: &ConjuntoAristas, cf: &mut ConjuntoFeromonas, p: Rho) {
+pub fn evapozacion_feromona(
+    _ca: &ConjuntoAristas,
+    cf: &mut ConjuntoFeromonas,
+    p: Rho,
+    limites: &LimitesFeromona,
+) {
     // Evaporación de feromona en todas las aristas
     // Fórmula: τ_ij = (1 - ρ) * τ_ij
 
-    for i in 0..cf.len() {
-        for j in 0..cf[i].len() {
+    for (i, fila) in cf.iter_mut().enumerate() {
+        for (j, feromona) in fila.iter_mut().enumerate() {
             if i != j {
                 // Aplicar evaporación
-                cf[i][j] *= 1.0 - p;
-
-                // Mantener un valor mínimo de feromona
+                *feromona *= 1.0 - p;
 
-                if cf[i][j] < 0.001 {
-                    cf[i][j] = 0.001;
-                }
+                // Mantener la feromona dentro de [τmin, τmax]
+                *feromona = limites.acotar(*feromona);
             }
         }
     }
 }
 
+pub fn diagnosticar(
+    ca: &ConjuntoAristas,
+    cf: &ConjuntoFeromonas,
+    iteraciones_sin_mejora: usize,
+) -> Diagnostico {
+    // Aristas salientes de cada nodo, sin el lazo i -> i
+    let rastros = cf.iter().enumerate().map(|(i, fila)| {
+        fila.iter()
+            .enumerate()
+            .filter(|&(j, _)| i != j && ca[i][j] != 0)
+            .map(|(_, &feromona)| feromona)
+            .collect()
+    });
+    diagnosticar_rastros(rastros, iteraciones_sin_mejora)
+}
+
 pub fn actualizacion_feromona(
     h: &Hormigas,
     cf: &mut ConjuntoFeromonas,
@@ -284,90 +612,230 @@ pub fn actualizacion_feromona(
         }
         let aportacion: Feromona = 1.0 / evaluacion.costo_total as Feromona;
 
-        for ruta in &hormiga.rutas {
-            // Para cada arista de la ruta (nodo_i -> nodo_j)
-            for ventana in ruta.windows(2) {
-                let origen_id = ventana[0];
-                let destino_id = ventana[1];
+        depositar_rutas(&hormiga.rutas, aportacion, cf);
+    }
+}
 
-                let origen_indice = (origen_id - 1) as usize;
-                let destino_indice = (destino_id - 1) as usize;
+fn depositar_rutas(rutas: &[Camino], aportacion: Feromona, cf: &mut ConjuntoFeromonas) {
+    for ruta in rutas {
+        // Para cada arista de la ruta (nodo_i -> nodo_j)
+        for ventana in ruta.windows(2) {
+            let origen_indice = (ventana[0] - 1) as usize;
+            let destino_indice = (ventana[1] - 1) as usize;
 
-                // Actualizar feromona
-                cf[origen_indice][destino_indice] += aportacion;
-                cf[destino_indice][origen_indice] += aportacion;
-            }
+            // Actualizar feromona
+            cf[origen_indice][destino_indice] += aportacion;
+            cf[destino_indice][origen_indice] += aportacion;
         }
     }
 }
 
-pub fn evaluacion_soluciones(
+pub fn actualizacion_feromona_estrategia(
+    configuracion: &Configuracion,
     h: &Hormigas,
-    cd: &ConjuntoDistancias,
-    _capacidad_maxima: u32,
-    n_vehiculos: u32,
-    _clientes: &Vec<Cliente>,
-) -> Evaluaciones {
-    let mut evaluaciones: Evaluaciones = Vec::with_capacity(h.len());
-
-    for hormiga in h.iter() {
-        let mut costo_total = 0u32;
-        let vehiculos_usados = hormiga.rutas.len() as u32;
-
-        // Verificar si usa más vehículos de los permitidos
-        if vehiculos_usados > n_vehiculos {
-            evaluaciones.push(EvaluacionSolucion {
-                costo_total: u32::MAX, // Penalización por usar demasiados vehículos
-                vehiculos_usados,
-                es_factible: false,
-            });
-            continue;
+    cf: &mut ConjuntoFeromonas,
+    evaluaciones: &Evaluaciones,
+    mejor_solucion: &Solucion,
+) {
+    // Hormigas factibles de la iteracion ordenadas de mejor a peor
+    let mut orden: Vec<usize> = (0..h.len())
+        .filter(|&k| evaluaciones[k].es_factible)
+        .collect();
+    orden.sort_by_key(|&k| evaluaciones[k].costo_total);
+
+    // La mejor global solo deposita si ya existe una solucion factible
+    let hay_mejor = mejor_solucion.costo_total != u32::MAX;
+
+    match configuracion.estrategia {
+        EstrategiaActualizacion::TodasLasHormigas => {
+            actualizacion_feromona(h, cf, evaluaciones);
+        }
+        EstrategiaActualizacion::Elitista => {
+            actualizacion_feromona(h, cf, evaluaciones);
+            if hay_mejor {
+                let aportacion =
+                    configuracion.peso_elitista / mejor_solucion.costo_total.max(1) as Feromona;
+                depositar_rutas(&mejor_solucion.rutas, aportacion, cf);
+            }
+        }
+        EstrategiaActualizacion::Rango => {
+            let w = configuracion.hormigas_rango.max(1);
+            for (r, &k) in orden.iter().take(w - 1).enumerate() {
+                // r empieza en 0: la mejor de la iteracion tiene rango 1
+                let aportacion =
+                    (w - r - 1) as Feromona / evaluaciones[k].costo_total.max(1) as Feromona;
+                depositar_rutas(&h[k].rutas, aportacion, cf);
+            }
+            if hay_mejor {
+                let aportacion = w as Feromona / mejor_solucion.costo_total.max(1) as Feromona;
+                depositar_rutas(&mejor_solucion.rutas, aportacion, cf);
+            }
+        }
+        EstrategiaActualizacion::MejorIteracion => {
+            if let Some(&k) = orden.first() {
+                let aportacion = 1.0 / evaluaciones[k].costo_total.max(1) as Feromona;
+                depositar_rutas(&h[k].rutas, aportacion, cf);
+            }
+        }
+    }
+}
+
+pub fn actualizacion_feromona_mmas(
+    rutas: &[Camino],
+    costo_total: u32,
+    cf: &mut ConjuntoFeromonas,
+    limites: &LimitesFeromona,
+) {
+    // Solo deposita la hormiga elegida (mejor de la iteracion o global)
+    let aportacion: Feromona = 1.0 / costo_total.max(1) as Feromona;
+
+    for ruta in rutas {
+        for ventana in ruta.windows(2) {
+            let origen_indice = (ventana[0] - 1) as usize;
+            let destino_indice = (ventana[1] - 1) as usize;
+
+            let feromona = (cf[origen_indice][destino_indice] + aportacion).min(limites.maximo);
+            cf[origen_indice][destino_indice] = feromona;
+            cf[destino_indice][origen_indice] = feromona;
         }
+    }
+}
 
-        let es_factible = true;
+// Demanda de cada cliente indexada por id (0 para ids sin cliente)
+pub fn demandas_por_id(clientes: &[Cliente]) -> Vec<u32> {
+    let max_id = clientes.iter().map(|c| c.id).max().unwrap_or(0) as usize;
+    let mut demandas = vec![0; max_id + 1];
+    for cliente in clientes {
+        demandas[cliente.id as usize] = cliente.demanda;
+    }
+    demandas
+}
 
-        // Evaluar cada ruta de la hormiga
-        for ruta in &hormiga.rutas {
-            let mut costo_ruta = 0u32;
+// Costo, carga por ruta y violaciones de una solucion. Es factible si cada
+// ruta sale y regresa al deposito sin exceder la capacidad, cada cliente se
+// visita exactamente una vez y no se usan mas vehiculos que la flota.
+pub fn evaluar_rutas(
+    rutas: &[Camino],
+    cd: &ConjuntoDistancias,
+    capacidad_maxima: u32,
+    n_vehiculos: u32,
+    clientes: &[Cliente],
+    deposito: NodoId,
+) -> EvaluacionSolucion {
+    let demandas = demandas_por_id(clientes);
+    let mut es_cliente = vec![false; demandas.len()];
+    for cliente in clientes.iter().filter(|c| c.id != deposito) {
+        es_cliente[cliente.id as usize] = true;
+    }
+    let mut visitas = vec![0u32; demandas.len()];
+    let mut violaciones = Vec::new();
+    let mut cargas = Vec::with_capacity(rutas.len());
+    let mut costo_total = 0u32;
+
+    let vehiculos_usados = rutas.len() as u32;
+    if vehiculos_usados > n_vehiculos {
+        violaciones.push(Violacion::DemasiadosVehiculos {
+            usados: vehiculos_usados,
+            disponibles: n_vehiculos,
+        });
+    }
 
-            // Calcular costo total de la ruta
-            for ventana in ruta.windows(2) {
-                let nodo_actual_id = ventana[0];
-                let nodo_siguiente_id = ventana[1];
+    for (r, ruta) in rutas.iter().enumerate() {
+        let numero = r + 1;
+        if ruta.len() < 2 || ruta[0] != deposito || ruta[ruta.len() - 1] != deposito {
+            violaciones.push(Violacion::RutaSinDeposito { ruta: numero });
+        }
 
-                let nodo_actual_indice = (nodo_actual_id - 1) as usize;
-                let nodo_siguiente_indice = (nodo_siguiente_id - 1) as usize;
+        // Ids fuera de la instancia no se pueden costear ni contar
+        let es_valido = |nodo: NodoId| nodo >= 1 && nodo as usize <= cd.len();
+        for &nodo in ruta.iter().filter(|&&nodo| !es_valido(nodo)) {
+            violaciones.push(Violacion::NodoInvalido { ruta: numero, nodo });
+        }
 
-                costo_ruta += cd[nodo_actual_indice][nodo_siguiente_indice] as u32;
+        // Clientes entre la salida y el regreso al deposito
+        let interior = ruta.get(1..ruta.len().saturating_sub(1)).unwrap_or(&[]);
+        let mut carga = 0u32;
+        for &nodo in interior.iter().filter(|&&nodo| es_valido(nodo)) {
+            if !es_cliente.get(nodo as usize).copied().unwrap_or(false) {
+                violaciones.push(Violacion::NodoNoCliente { ruta: numero, nodo });
+                continue;
+            }
+            visitas[nodo as usize] += 1;
+            carga += demandas[nodo as usize];
+        }
+        if carga > capacidad_maxima {
+            violaciones.push(Violacion::Capacidad {
+                ruta: numero,
+                carga,
+                capacidad: capacidad_maxima,
+            });
+        }
+        cargas.push(CargaRuta {
+            carga,
+            holgura: capacidad_maxima as i64 - carga as i64,
+        });
+
+        for ventana in ruta.windows(2) {
+            if !es_valido(ventana[0]) || !es_valido(ventana[1]) {
+                continue;
             }
-            costo_total += costo_ruta;
+            let nodo_actual_indice = (ventana[0] - 1) as usize;
+            let nodo_siguiente_indice = (ventana[1] - 1) as usize;
+            costo_total += cd[nodo_actual_indice][nodo_siguiente_indice] as u32;
         }
+    }
 
-        let evaluacion = EvaluacionSolucion {
-            costo_total,
-            vehiculos_usados,
-            es_factible,
-        };
+    for cliente in clientes.iter().filter(|c| c.id != deposito) {
+        match visitas[cliente.id as usize] {
+            1 => {}
+            0 => violaciones.push(Violacion::ClienteNoVisitado {
+                cliente: cliente.id,
+            }),
+            veces => violaciones.push(Violacion::ClienteRepetido {
+                cliente: cliente.id,
+                visitas: veces,
+            }),
+        }
+    }
 
-        evaluaciones.push(evaluacion);
+    EvaluacionSolucion {
+        costo_total,
+        vehiculos_usados,
+        es_factible: violaciones.is_empty(),
+        cargas,
+        violaciones,
     }
+}
 
-    evaluaciones
+pub fn evaluacion_soluciones(
+    h: &Hormigas,
+    cd: &ConjuntoDistancias,
+    capacidad_maxima: u32,
+    n_vehiculos: u32,
+    clientes: &[Cliente],
+    deposito: NodoId,
+) -> Evaluaciones {
+    h.iter()
+        .map(|hormiga| {
+            evaluar_rutas(
+                &hormiga.rutas,
+                cd,
+                capacidad_maxima,
+                n_vehiculos,
+                clientes,
+                deposito,
+            )
+        })
+        .collect()
 }
 
 pub fn calcular_conjunto_distancias(datos: &DatosVRP) -> ConjuntoDistancias {
     let n = datos.dimension as usize;
     let mut conjunto_distancias = vec![vec![0.0; n]; n];
 
-    for i in 0..n {
-        for j in 0..n {
-            if i == j {
-                conjunto_distancias[i][j] = 0.0;
-            } else {
-                // Obtener coordenadas de los nodos usando índices
-                let nodo_i = &datos.nodos[i];
-                let nodo_j = &datos.nodos[j];
-
+    for (i, nodo_i) in datos.nodos.iter().enumerate().take(n) {
+        for (j, nodo_j) in datos.nodos.iter().enumerate().take(n) {
+            if i != j {
                 // Calcular distancia euclidiana: sqrt((x2-x1)² + (y2-y1)²)
                 let dx = (nodo_j.x as f64 - nodo_i.x as f64).powi(2);
                 let dy = (nodo_j.y as f64 - nodo_i.y as f64).powi(2);
@@ -375,7 +843,6 @@ pub fn calcular_conjunto_distancias(datos: &DatosVRP) -> ConjuntoDistancias {
 
                 // Asignar distancia al conjunto
                 conjunto_distancias[i][j] = distancia;
-                conjunto_distancias[j][i] = distancia;
             }
         }
     }
@@ -386,11 +853,10 @@ pub fn calcular_conjunto_distancias(datos: &DatosVRP) -> ConjuntoDistancias {
 pub fn create_conjunto_aristas(dimension: usize) -> ConjuntoAristas {
     let mut ca: ConjuntoAristas = vec![vec![0; dimension]; dimension];
 
-    for i in 0..dimension {
-        for j in 0..dimension {
+    for (i, fila) in ca.iter_mut().enumerate() {
+        for (j, arista) in fila.iter_mut().enumerate() {
             if i != j {
-                ca[i][j] = 1;
-                ca[j][i] = 1;
+                *arista = 1;
             }
         }
     }
@@ -416,9 +882,9 @@ pub fn leer_matriz(path: String) -> DatosVRP {
         let linea = linea.expect("Error leyendo línea");
         let ltrim = linea.trim();
 
-        if ltrim.starts_with("COMMENT :") {
+        if let Some(comment) = ltrim.strip_prefix("COMMENT :") {
             // Ejemplo: COMMENT : (Augerat et al, No of trucks: 5, Optimal value: 784)
-            let comment = ltrim["COMMENT :".len()..].trim();
+            let comment = comment.trim();
             if let Some(indice) = comment.find("No of trucks:") {
                 let after = &comment[indice + "No of trucks:".len()..];
                 if let Some(end) = after.find(',') {
@@ -435,16 +901,10 @@ pub fn leer_matriz(path: String) -> DatosVRP {
                 }
             }
         }
-        if ltrim.starts_with("DIMENSION :") {
-            dimension = ltrim["DIMENSION :".len()..]
-                .trim()
-                .parse()
-                .expect("Error parseando DIMENSION");
-        } else if ltrim.starts_with("CAPACITY :") {
-            capacidad = ltrim["CAPACITY :".len()..]
-                .trim()
-                .parse()
-                .expect("Error parseando CAPACITY");
+        if let Some(valor) = ltrim.strip_prefix("DIMENSION :") {
+            dimension = valor.trim().parse().expect("Error parseando DIMENSION");
+        } else if let Some(valor) = ltrim.strip_prefix("CAPACITY :") {
+            capacidad = valor.trim().parse().expect("Error parseando CAPACITY");
         } else if ltrim.starts_with("NODE_COORD_SECTION") {
             seccion = "NODE_COORD_SECTION";
             continue;
@@ -518,18 +978,154 @@ pub fn leer_valor_optimo(filepath_vrp: &str) -> Option<u32> {
     let reader = BufReader::new(file);
 
     // Buscar la línea que contiene "Cost"
-    for linea in reader.lines() {
-        if let Ok(linea_contenido) = linea {
-            let linea_trim = linea_contenido.trim();
-            if linea_trim.starts_with("Cost ") {
-                // Extraer el número después de "Cost "
-                let costo_str = linea_trim["Cost ".len()..].trim();
-                if let Ok(costo) = costo_str.parse::<u32>() {
-                    return Some(costo);
-                }
+    for linea_contenido in reader.lines().map_while(Result::ok) {
+        let linea_trim = linea_contenido.trim();
+        if let Some(costo_str) = linea_trim.strip_prefix("Cost ") {
+            // Extraer el número después de "Cost "
+            if let Ok(costo) = costo_str.trim().parse::<u32>() {
+                return Some(costo);
             }
         }
     }
 
     None // Si no se encuentra el costo
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Deposito 1 y clientes 2, 3 y 4 con demandas 3, 4 y 5; capacidad 8.
+    // Todas las aristas cuestan 10 salvo las que salen del deposito, 1.
+    fn instancia() -> (ConjuntoDistancias, Vec<Cliente>) {
+        let cd = (0..4)
+            .map(|i| {
+                (0..4)
+                    .map(|j| match (i, j) {
+                        _ if i == j => 0.0,
+                        (0, _) => 1.0,
+                        _ => 10.0,
+                    })
+                    .collect()
+            })
+            .collect();
+        let clientes = [(1, 0), (2, 3), (3, 4), (4, 5)]
+            .into_iter()
+            .map(|(id, demanda)| Cliente { id, demanda })
+            .collect();
+        (cd, clientes)
+    }
+
+    fn evaluar(rutas: &[Camino]) -> EvaluacionSolucion {
+        let (cd, clientes) = instancia();
+        evaluar_rutas(rutas, &cd, 8, 3, &clientes, 1)
+    }
+
+    #[test]
+    fn solucion_factible() {
+        let evaluacion = evaluar(&[vec![1, 2, 3, 1], vec![1, 4, 1]]);
+        assert!(evaluacion.es_factible, "{:?}", evaluacion.violaciones);
+        assert_eq!(evaluacion.costo_total, 1 + 10 + 10 + 1 + 10);
+        assert_eq!(evaluacion.cargas[0].holgura, 1);
+    }
+
+    #[test]
+    fn exceso_de_capacidad() {
+        let evaluacion = evaluar(&[vec![1, 3, 4, 1], vec![1, 2, 1]]);
+        assert!(!evaluacion.es_factible);
+        assert_eq!(
+            evaluacion.violaciones,
+            vec![Violacion::Capacidad {
+                ruta: 1,
+                carga: 9,
+                capacidad: 8,
+            }]
+        );
+        assert_eq!(evaluacion.cargas[0].holgura, -1);
+    }
+
+    #[test]
+    fn cliente_repetido() {
+        let evaluacion = evaluar(&[vec![1, 2, 3, 1], vec![1, 4, 2, 1]]);
+        assert!(evaluacion
+            .violaciones
+            .contains(&Violacion::ClienteRepetido {
+                cliente: 2,
+                visitas: 2,
+            }));
+    }
+
+    #[test]
+    fn cliente_sin_visitar() {
+        let evaluacion = evaluar(&[vec![1, 2, 3, 1]]);
+        assert_eq!(
+            evaluacion.violaciones,
+            vec![Violacion::ClienteNoVisitado { cliente: 4 }]
+        );
+    }
+
+    #[test]
+    fn nodos_fuera_de_la_instancia() {
+        // Antes el id 0 desbordaba al restar 1 para indexar las distancias
+        let evaluacion = evaluar(&[vec![1, 0, 2, 3, 1], vec![1, 4, 9, 1]]);
+        assert!(!evaluacion.es_factible);
+        assert!(evaluacion
+            .violaciones
+            .contains(&Violacion::NodoInvalido { ruta: 1, nodo: 0 }));
+        assert!(evaluacion
+            .violaciones
+            .contains(&Violacion::NodoInvalido { ruta: 2, nodo: 9 }));
+        assert!(!evaluacion
+            .violaciones
+            .iter()
+            .any(|v| matches!(v, Violacion::NodoNoCliente { .. })));
+        // Solo se costean las aristas entre nodos validos
+        assert_eq!(evaluacion.costo_total, 10 + 10 + 1);
+    }
+
+    // Instancias CVRP del generador leidas como las del conjunto A
+    #[test]
+    fn lee_instancias_generadas() {
+        use generador_instancias::generador::{
+            generar_instancia, Demanda, Parametros, TipoInstancia,
+        };
+
+        let ruta = std::env::temp_dir()
+            .join(format!("{}_generada.vrp", std::process::id()))
+            .to_string_lossy()
+            .into_owned();
+        let leer = |parametros: &Parametros| {
+            generar_instancia(&ruta, parametros).unwrap();
+            let datos = leer_matriz(ruta.clone());
+            std::fs::remove_file(&ruta).unwrap();
+            datos
+        };
+
+        // 12 clientes de demanda 1 en 2 vehiculos al 90 %: ceil(12 / 1.8) = 7
+        let unitaria = Parametros {
+            demanda: Demanda::Unitaria,
+            ..Parametros::new(TipoInstancia::Cvrp, 13, 4)
+        };
+        let datos = leer(&unitaria);
+        assert_eq!(datos.dimension, 13);
+        assert_eq!(datos.nodos.len(), 13);
+        assert_eq!(datos.capacidad, 7);
+        assert_eq!(datos.n_vehiculos, 2);
+        assert_eq!(datos.deposito_id, 1);
+        let demandas = demandas_por_id(&datos.clientes);
+        assert_eq!(demandas[1], 0);
+        assert!((2..=13).all(|id| demandas[id] == 1));
+
+        let uniforme = Parametros {
+            vehiculos: Some(5),
+            ..Parametros::new(TipoInstancia::Cvrp, 40, 4)
+        };
+        let datos = leer(&uniforme);
+        assert_eq!((datos.dimension, datos.n_vehiculos), (40, 5));
+        let demandas = demandas_por_id(&datos.clientes);
+        let total: u32 = demandas.iter().sum();
+        assert!((2..=40).all(|id| (1..=30).contains(&demandas[id])));
+        assert!(datos.capacidad >= *demandas.iter().max().unwrap());
+        assert!(total as f64 <= 0.9 * (datos.capacidad * 5) as f64);
+    }
+}
@@ -0,0 +1,257 @@
+use crate::rutas_vehiculares::*;
+
+// Construccion "ruta primero, agrupacion despues": cada hormiga construye un
+// solo tour sobre todos los clientes, sin considerar la capacidad, como en el
+// ATSP, y el Split de Prins lo parte de forma optima en rutas factibles.
+//
+// Split: en el grafo auxiliar los nodos son las posiciones 0..=n del tour y
+// el arco (i, j) es la ruta deposito, t[i], ..., t[j - 1], deposito, presente
+// si su carga cabe en el vehiculo. El camino mas corto de 0 a n da la mejor
+// particion que respeta el orden del tour. El grafo es aciclico, asi que
+// basta un recorrido de Bellman en orden; para respetar la flota se hace por
+// capas, una por vehiculo.
+
+// Como construye cada hormiga sus rutas
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ModoConstruccion {
+    // Una ruta a la vez, agregando clientes mientras quepan
+    RutasSecuenciales,
+    // Tour gigante partido con Split
+    TourGigante,
+}
+
+fn d(cd: &ConjuntoDistancias, a: NodoId, b: NodoId) -> u64 {
+    cd[(a - 1) as usize][(b - 1) as usize] as u32 as u64
+}
+
+// Mejor particion del tour en a lo mas `n_vehiculos` rutas. Si el orden del
+// tour no lo permite, la particion con menos rutas (que `evaluar_rutas`
+// marcara por exceder la flota). None si algun cliente no cabe solo en un
+// vehiculo.
+pub fn split_optimo(
+    tour: &[NodoId],
+    cd: &ConjuntoDistancias,
+    demandas: &[u32],
+    capacidad: u32,
+    deposito: NodoId,
+    n_vehiculos: usize,
+) -> Option<Vec<Camino>> {
+    let n = tour.len();
+    if tour
+        .iter()
+        .any(|&cliente| demandas[cliente as usize] > capacidad)
+    {
+        return None;
+    }
+
+    // costos[i]: menor costo de servir t[0..i] con k rutas (capa actual)
+    let mut costos = vec![u64::MAX; n + 1];
+    costos[0] = 0;
+    let mut predecesores: Vec<Vec<usize>> = Vec::new();
+    // (costo, rutas) de la mejor particion completa encontrada
+    let mut mejor: Option<(u64, usize)> = None;
+
+    for k in 1..=n {
+        let mut siguiente = vec![u64::MAX; n + 1];
+        let mut predecesor = vec![usize::MAX; n + 1];
+        for (i, &base) in costos.iter().enumerate().take(n) {
+            if base == u64::MAX {
+                continue;
+            }
+            let mut carga = 0;
+            let mut costo = 0;
+            for j in i + 1..=n {
+                let cliente = tour[j - 1];
+                carga += demandas[cliente as usize];
+                if carga > capacidad {
+                    break;
+                }
+                // Extender la ruta: el regreso al deposito pasa a ser del
+                // nuevo ultimo cliente
+                costo = if j == i + 1 {
+                    d(cd, deposito, cliente) + d(cd, cliente, deposito)
+                } else {
+                    let anterior = tour[j - 2];
+                    costo - d(cd, anterior, deposito)
+                        + d(cd, anterior, cliente)
+                        + d(cd, cliente, deposito)
+                };
+                if base + costo < siguiente[j] {
+                    siguiente[j] = base + costo;
+                    predecesor[j] = i;
+                }
+            }
+        }
+        predecesores.push(predecesor);
+
+        // Dentro de la flota se busca el menor costo; fuera de ella basta
+        // la primera capa que cubre el tour
+        if siguiente[n] != u64::MAX
+            && (k > n_vehiculos || mejor.is_none_or(|(costo, _)| siguiente[n] < costo))
+        {
+            mejor = Some((siguiente[n], k));
+        }
+        if k >= n_vehiculos && mejor.is_some() {
+            break;
+        }
+        costos = siguiente;
+    }
+
+    let Some((_, n_rutas)) = mejor else {
+        return Some(Vec::new());
+    };
+    let mut rutas = Vec::with_capacity(n_rutas);
+    let mut j = n;
+    for predecesor in predecesores[..n_rutas].iter().rev() {
+        let i = predecesor[j];
+        let mut ruta = Vec::with_capacity(j - i + 2);
+        ruta.push(deposito);
+        ruta.extend_from_slice(&tour[i..j]);
+        ruta.push(deposito);
+        rutas.push(ruta);
+        j = i;
+    }
+    rutas.reverse();
+    Some(rutas)
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn construccion_tour_gigante(
+    candidatos: &ListasCandidatos,
+    cf: &ConjuntoFeromonas,
+    cd: &ConjuntoDistancias,
+    h: &mut Hormigas,
+    capacidad_maxima: u32,
+    n_vehiculos: u32,
+    i_f: ImportanciaFeromona,
+    i_d: ImportanciaDistancia,
+    visibilidad: &Visibilidad,
+    deposito: NodoId,
+    clientes: &[Cliente],
+) {
+    let demandas = demandas_por_id(clientes);
+    let n_clientes = clientes.iter().filter(|c| c.id != deposito).count();
+
+    for hormiga in h.iter_mut() {
+        let mut visitados = Visitados::new(demandas.len());
+        visitados.insertar(deposito);
+        let mut tour: Camino = Vec::with_capacity(n_clientes);
+        let mut nodo_actual = deposito;
+
+        while tour.len() < n_clientes {
+            // Candidatos sin visitar del nodo actual; si no queda ninguno,
+            // todos los clientes sin visitar
+            let mut vertices_factibles: Vec<NodoId> = candidatos[(nodo_actual - 1) as usize]
+                .iter()
+                .copied()
+                .filter(|&id| !visitados.contiene(id))
+                .collect();
+            if vertices_factibles.is_empty() {
+                vertices_factibles = clientes
+                    .iter()
+                    .map(|c| c.id)
+                    .filter(|&id| !visitados.contiene(id))
+                    .collect();
+            }
+
+            // Sin capacidad en el tour, el modelo de ahorros solo aporta el
+            // ahorro (utilizacion 1)
+            let termino_adicional = |destino: NodoId| {
+                visibilidad.termino_adicional(cd, deposito, nodo_actual, destino, 1.0)
+            };
+            let Some(siguiente_nodo) = seleccion_ruleta(
+                nodo_actual,
+                &vertices_factibles,
+                cf,
+                cd,
+                i_d,
+                i_f,
+                termino_adicional,
+            ) else {
+                break;
+            };
+            tour.push(siguiente_nodo);
+            visitados.insertar(siguiente_nodo);
+            nodo_actual = siguiente_nodo;
+        }
+
+        hormiga.rutas = split_optimo(
+            &tour,
+            cd,
+            &demandas,
+            capacidad_maxima,
+            deposito,
+            n_vehiculos as usize,
+        )
+        .unwrap_or_default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Deposito 1 en 0 de una recta; los clientes 2 y 5 en 1 y los clientes 3
+    // y 4 en 10, todos con demanda 5 y capacidad 10
+    fn instancia() -> (ConjuntoDistancias, Vec<u32>) {
+        let posiciones: [f64; 5] = [0.0, 1.0, 10.0, 10.0, 1.0];
+        let cd = posiciones
+            .iter()
+            .map(|a| posiciones.iter().map(|b| (a - b).abs()).collect())
+            .collect();
+        (cd, vec![0, 0, 5, 5, 5, 5])
+    }
+
+    const TOUR: [NodoId; 4] = [2, 3, 4, 5];
+    const CAPACIDAD: u32 = 10;
+
+    fn costo(rutas: &[Camino], cd: &ConjuntoDistancias) -> u64 {
+        rutas
+            .iter()
+            .flat_map(|ruta| ruta.windows(2))
+            .map(|arista| d(cd, arista[0], arista[1]))
+            .sum()
+    }
+
+    #[test]
+    fn split_supera_al_llenado_voraz() {
+        let (cd, demandas) = instancia();
+        // Llenar cada vehiculo en el orden del tour: {2, 3} y {4, 5}
+        let voraz = vec![vec![1, 2, 3, 1], vec![1, 4, 5, 1]];
+        assert_eq!(costo(&voraz, &cd), 40);
+
+        // Split deja solos a 2 y 5 y junta a 3 y 4: 2 + 20 + 2
+        let rutas = split_optimo(&TOUR, &cd, &demandas, CAPACIDAD, 1, 5).unwrap();
+        assert_eq!(rutas, vec![vec![1, 2, 1], vec![1, 3, 4, 1], vec![1, 5, 1]]);
+        assert_eq!(costo(&rutas, &cd), 24);
+    }
+
+    #[test]
+    fn la_flota_obliga_a_otra_capa() {
+        let (cd, demandas) = instancia();
+        // Con dos vehiculos la unica particion es la voraz
+        let rutas = split_optimo(&TOUR, &cd, &demandas, CAPACIDAD, 1, 2).unwrap();
+        assert_eq!(rutas, vec![vec![1, 2, 3, 1], vec![1, 4, 5, 1]]);
+        assert_eq!(costo(&rutas, &cd), 40);
+
+        // Si ninguna particion cabe en la flota, la de menos rutas
+        let rutas = split_optimo(&TOUR, &cd, &demandas, CAPACIDAD, 1, 1).unwrap();
+        assert_eq!(rutas.len(), 2);
+    }
+
+    #[test]
+    fn tour_vacio_no_tiene_rutas() {
+        let (cd, demandas) = instancia();
+        assert_eq!(
+            split_optimo(&[], &cd, &demandas, CAPACIDAD, 1, 3),
+            Some(Vec::new())
+        );
+    }
+
+    #[test]
+    fn cliente_mayor_que_la_capacidad() {
+        let (cd, mut demandas) = instancia();
+        demandas[3] = CAPACIDAD + 1;
+        assert_eq!(split_optimo(&TOUR, &cd, &demandas, CAPACIDAD, 1, 5), None);
+    }
+}
@@ -0,0 +1,338 @@
+use crate::sistema_hormigas::*;
+
+// ATSP biobjetivo: dos matrices de pesos sobre las mismas ciudades (p. ej.
+// distancia y peaje). Colonia con una matriz de feromona por objetivo al
+// estilo P-ACO (Doerner et al.): la hormiga k de m combina ambas con el peso
+// λ_k = k / (m - 1),
+//   p_ij ∝ (τ1_ij^λ τ2_ij^(1-λ))^α (η1_ij^λ η2_ij^(1-λ))^β
+// de modo que cada hormiga busca una region distinta del frente. La mejor
+// hormiga de la iteracion en cada objetivo deposita en la matriz de ese
+// objetivo, y todas las soluciones no dominadas se guardan en un archivo.
+
+pub type Objetivos = [u64; 2];
+
+#[derive(Debug, Clone)]
+pub struct SolucionPareto {
+    pub camino: Camino,
+    pub costos: Objetivos,
+}
+
+// Soluciones mutuamente no dominadas, ordenadas por el primer objetivo
+pub type ArchivoPareto = Vec<SolucionPareto>;
+
+// a domina a b si no es peor en ningun objetivo y es mejor en alguno
+pub fn domina(a: &Objetivos, b: &Objetivos) -> bool {
+    a.iter().zip(b).all(|(x, y)| x <= y) && a != b
+}
+
+// Agrega la solucion si nadie en el archivo la domina ni tiene los mismos
+// costos, y elimina las que pasa a dominar
+pub fn insertar_archivo(archivo: &mut ArchivoPareto, solucion: SolucionPareto) -> bool {
+    if archivo
+        .iter()
+        .any(|otra| otra.costos == solucion.costos || domina(&otra.costos, &solucion.costos))
+    {
+        return false;
+    }
+    archivo.retain(|otra| !domina(&solucion.costos, &otra.costos));
+    let posicion = archivo.partition_point(|otra| otra.costos < solucion.costos);
+    archivo.insert(posicion, solucion);
+    true
+}
+
+// Area dominada por el frente y acotada por el punto de referencia. Con el
+// frente ordenado por el primer objetivo (y decreciente en el segundo) es
+// una suma de rectangulos; los puntos fuera de la referencia no aportan.
+pub fn hipervolumen(archivo: &ArchivoPareto, referencia: (f64, f64)) -> f64 {
+    let mut area = 0.0;
+    let mut techo = referencia.1;
+    for solucion in archivo {
+        let (f1, f2) = (solucion.costos[0] as f64, solucion.costos[1] as f64);
+        if f1 >= referencia.0 || f2 >= techo {
+            continue;
+        }
+        area += (referencia.0 - f1) * (techo - f2);
+        techo = f2;
+    }
+    area
+}
+
+// Punto de referencia por omision: 10 % por encima del peor valor de cada
+// objetivo en el archivo
+pub fn referencia_nadir(archivo: &ArchivoPareto) -> (f64, f64) {
+    let peor = |o: usize| {
+        archivo
+            .iter()
+            .map(|solucion| solucion.costos[o])
+            .max()
+            .unwrap_or(0) as f64
+    };
+    (peor(0) * 1.1, peor(1) * 1.1)
+}
+
+// Aristas presentes en ambas instancias
+fn interseccion_aristas(a: &Base, b: &Base) -> Base {
+    a.iter()
+        .zip(b)
+        .map(|(vecinos_a, vecinos_b)| {
+            vecinos_a
+                .iter()
+                .copied()
+                .filter(|j| vecinos_b.contains(j))
+                .collect()
+        })
+        .collect()
+}
+
+// Union de los k vecinos mas cercanos en cada objetivo
+fn candidatos_biobjetivo(
+    conjunto_aristas: &Base,
+    distancias: [&Distancias; 2],
+    k: usize,
+) -> ListasCandidatos {
+    let primeros = listas_candidatos(conjunto_aristas, distancias[0], k);
+    let segundos = listas_candidatos(conjunto_aristas, distancias[1], k);
+    primeros
+        .into_iter()
+        .zip(segundos)
+        .map(|(mut lista, otros)| {
+            for j in otros {
+                if !lista.contains(&j) {
+                    lista.push(j);
+                }
+            }
+            lista
+        })
+        .collect()
+}
+
+// Solo el piso τmin de MMAS, con el mejor costo conocido en un objetivo
+fn limites_objetivo(costo: u64, p: Rho, n: usize, p_mejor: f64) -> LimitesFeromona {
+    let costo = costo.min(u32::MAX as u64) as u32;
+    LimitesFeromona::new(costo, p, n, p_mejor).sin_maximo()
+}
+
+// Peso λ de la hormiga k entre m
+fn peso_hormiga(k: usize, m: usize) -> f64 {
+    if m <= 1 {
+        0.5
+    } else {
+        k as f64 / (m - 1) as f64
+    }
+}
+
+pub fn sistema_hormigas_biobjetivo(
+    configuracion: &Configuracion,
+    primera: &MatrizFromFile,
+    segunda: &MatrizFromFile,
+) -> ArchivoPareto {
+    let n = primera.dimension as usize;
+    let conjunto_aristas = interseccion_aristas(
+        &get_conjunto_aristas_from_distancia(primera, &configuracion.centinelas),
+        &get_conjunto_aristas_from_distancia(segunda, &configuracion.centinelas),
+    );
+    let distancias = [&primera.matriz, &segunda.matriz];
+    let candidatos =
+        candidatos_biobjetivo(&conjunto_aristas, distancias, configuracion.n_candidatos);
+    let sin_precedencias: Precedencias = vec![Vec::new(); n];
+    let p: Rho = configuracion.rho;
+    let (i_f, i_d) = (
+        configuracion.importancia_feromona,
+        configuracion.importancia_distancia,
+    );
+
+    let mut feromonas = [
+        algoritmo_inicializacion(&conjunto_aristas),
+        algoritmo_inicializacion(&conjunto_aristas),
+    ];
+    // Piso τmin de MMAS en cada objetivo para que la evaporacion no lleve τ
+    // a 0 (ln τ = -∞, y con λ = 0 el valor de eleccion seria NaN). Se estima
+    // con el vecino mas cercano hasta que el archivo tenga soluciones.
+    let inicio = get_inicio(&primera.dimension);
+    let limites_objetivo = |costo: u64| limites_objetivo(costo, p, n, configuracion.p_mejor);
+    let mut limites = distancias.map(|distancias| {
+        limites_objetivo(costo_vecino_mas_cercano(&conjunto_aristas, distancias, inicio) as u64)
+    });
+    // ln η_ij de cada objetivo; el valor de eleccion de cada hormiga es
+    // exp(λ (α ln τ1 + β ln η1) + (1 - λ) (α ln τ2 + β ln η2)). Las aristas
+    // de peso 0 se tratan como de peso 1 para que λ = 0 no de 0 * ∞.
+    let log_visibilidad = distancias.map(|distancias| {
+        MatrizAristas::new(&conjunto_aristas, |i, j| {
+            -(distancias[i as usize][j as usize].max(1) as Peso).ln()
+        })
+    });
+
+    // Matrices de trabajo con la forma de las feromonas, recalculadas en su
+    // lugar: la atraccion de cada objetivo por iteracion y los valores de
+    // eleccion por hormiga
+    let mut atraccion = feromonas.clone();
+    let mut valores_eleccion = feromonas[0].clone();
+
+    let mut archivo: ArchivoPareto = Vec::new();
+
+    for i in 1..=configuracion.max_iteraciones {
+        print!("\rProgreso: {}/{}", i, configuracion.max_iteraciones);
+        std::io::Write::flush(&mut std::io::stdout()).unwrap();
+
+        for (o, atraccion) in atraccion.iter_mut().enumerate() {
+            atraccion.actualizar(&conjunto_aristas, |i, j| {
+                i_f * feromonas[o].get(i, j).ln() + i_d * log_visibilidad[o].get(i, j)
+            });
+        }
+
+        let mut soluciones: Vec<SolucionPareto> = Vec::with_capacity(configuracion.n_hormigas);
+        for k in 0..configuracion.n_hormigas {
+            let lambda = peso_hormiga(k, configuracion.n_hormigas);
+            valores_eleccion.actualizar(&conjunto_aristas, |i, j| {
+                (lambda * atraccion[0].get(i, j) + (1.0 - lambda) * atraccion[1].get(i, j)).exp()
+            });
+            let mut hormigas = inicializacion_hormigas(1, inicio);
+            construccion_caminos(
+                &conjunto_aristas,
+                &candidatos,
+                &sin_precedencias,
+                &valores_eleccion,
+                &mut hormigas,
+            );
+            let camino = hormigas.pop().unwrap().camino;
+            if !camino_completo(&conjunto_aristas, &camino) {
+                continue;
+            }
+            let costos = distancias.map(|distancias| costo_camino_cerrado(&camino, distancias));
+            soluciones.push(SolucionPareto { camino, costos });
+        }
+
+        for solucion in &soluciones {
+            insertar_archivo(&mut archivo, solucion.clone());
+        }
+
+        // Evaporacion y deposito 1 / f_o de la mejor de la iteracion en o
+        for (o, feromonas) in feromonas.iter_mut().enumerate() {
+            evapozacion_feromona(&conjunto_aristas, feromonas, p);
+            let Some(mejor) = soluciones.iter().min_by_key(|solucion| solucion.costos[o]) else {
+                continue;
+            };
+            let aportacion = 1.0 / mejor.costos[o].max(1) as Peso;
+            depositar_camino(&mejor.camino, aportacion, feromonas);
+        }
+
+        // Piso de cada matriz con el mejor costo del archivo en su objetivo
+        for (o, feromonas) in feromonas.iter_mut().enumerate() {
+            if let Some(mejor) = archivo.iter().map(|solucion| solucion.costos[o]).min() {
+                limites[o] = limites_objetivo(mejor);
+            }
+            limitar_feromona(&conjunto_aristas, feromonas, &limites[o]);
+        }
+    }
+    println!();
+    archivo
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn solucion(costos: Objetivos) -> SolucionPareto {
+        SolucionPareto {
+            camino: Vec::new(),
+            costos,
+        }
+    }
+
+    fn costos(archivo: &ArchivoPareto) -> Vec<Objetivos> {
+        archivo.iter().map(|solucion| solucion.costos).collect()
+    }
+
+    #[test]
+    fn dominancia() {
+        assert!(domina(&[1, 2], &[2, 2]));
+        assert!(domina(&[1, 1], &[2, 3]));
+        assert!(!domina(&[1, 3], &[2, 2]));
+        assert!(!domina(&[2, 2], &[2, 2]));
+        assert!(!domina(&[3, 3], &[2, 2]));
+    }
+
+    #[test]
+    fn archivo_ordenado_y_sin_dominados() {
+        let mut archivo: ArchivoPareto = Vec::new();
+        assert!(insertar_archivo(&mut archivo, solucion([4, 1])));
+        assert!(insertar_archivo(&mut archivo, solucion([1, 5])));
+        assert!(insertar_archivo(&mut archivo, solucion([3, 3])));
+        assert_eq!(costos(&archivo), vec![[1, 5], [3, 3], [4, 1]]);
+
+        // Dominada por [3, 3] y repetida
+        assert!(!insertar_archivo(&mut archivo, solucion([3, 4])));
+        assert!(!insertar_archivo(&mut archivo, solucion([1, 5])));
+        assert_eq!(archivo.len(), 3);
+
+        // [2, 2] domina a [3, 3]
+        assert!(insertar_archivo(&mut archivo, solucion([2, 2])));
+        assert_eq!(costos(&archivo), vec![[1, 5], [2, 2], [4, 1]]);
+    }
+
+    #[test]
+    fn hipervolumen_de_un_frente_escalonado() {
+        let archivo: ArchivoPareto = [[1, 5], [2, 3], [4, 1]].map(solucion).to_vec();
+        // Columnas [1, 2] x [5, 6], [2, 4] x [3, 6] y [4, 5] x [1, 6]
+        assert_eq!(hipervolumen(&archivo, (5.0, 6.0)), 1.0 + 6.0 + 5.0);
+        // Los puntos fuera de la referencia no aportan
+        assert_eq!(hipervolumen(&archivo, (3.0, 4.0)), 1.0);
+        assert_eq!(hipervolumen(&archivo, (1.0, 1.0)), 0.0);
+        assert_eq!(hipervolumen(&Vec::new(), (5.0, 6.0)), 0.0);
+    }
+
+    #[test]
+    fn el_piso_mantiene_finito_el_logaritmo() {
+        let file = leer_matriz("src/files/br17.txt").unwrap();
+        let conjunto_aristas = get_conjunto_aristas_from_distancia(&file, &[]);
+        let limites = limites_objetivo(39, 0.99, 17, 0.05);
+        let mut feromonas = algoritmo_inicializacion(&conjunto_aristas);
+        for _ in 0..500 {
+            evapozacion_feromona(&conjunto_aristas, &mut feromonas, 0.99);
+            limitar_feromona(&conjunto_aristas, &mut feromonas, &limites);
+        }
+        for (i, vecinos) in conjunto_aristas.iter().enumerate() {
+            for &j in vecinos {
+                let feromona = feromonas.get(i as Nodo, j);
+                assert_eq!(feromona, limites.minimo);
+                // λ = 0 multiplica ln τ por 0; sin piso seria 0 * -∞ = NaN
+                assert!((0.0 * feromona.ln()).is_finite());
+            }
+        }
+    }
+
+    // br17 contra su transpuesta (el mismo camino recorrido al reves), con
+    // evaporacion casi total
+    #[test]
+    fn evaporacion_fuerte_sin_perder_el_frente() {
+        let primera = leer_matriz("src/files/br17.txt").unwrap();
+        let mut segunda = leer_matriz("src/files/br17.txt").unwrap();
+        let n = segunda.matriz.len();
+        segunda.matriz = (0..n)
+            .map(|i| (0..n).map(|j| primera.matriz[j][i]).collect())
+            .collect();
+        let configuracion = Configuracion {
+            rho: 0.99,
+            max_iteraciones: 300,
+            ..Configuracion::default()
+        };
+
+        fijar_semilla(2);
+        let archivo = sistema_hormigas_biobjetivo(&configuracion, &primera, &segunda);
+        assert!(!archivo.is_empty());
+        for (k, solucion) in archivo.iter().enumerate() {
+            assert_eq!(solucion.camino.len(), n);
+            assert_eq!(
+                solucion.costos,
+                [
+                    costo_camino_cerrado(&solucion.camino, &primera.matriz),
+                    costo_camino_cerrado(&solucion.camino, &segunda.matriz),
+                ]
+            );
+            for otra in &archivo[k + 1..] {
+                assert!(solucion.costos[0] < otra.costos[0]);
+                assert!(solucion.costos[1] > otra.costos[1]);
+            }
+        }
+    }
+}
@@ -0,0 +1,360 @@
+use crate::sistema_hormigas::{Camino, Distancias, Hormigas, Nodo, Precedencias};
+
+// Busqueda local para caminos del ATSP. Ningun movimiento invierte un tramo
+// del camino, asi que el costo de cada movimiento se calcula solo con las
+// aristas que se quitan y se agregan, aunque la matriz sea asimetrica.
+
+// A que hormigas se aplica la busqueda local en cada iteracion
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BusquedaLocal {
+    Ninguna,
+    TodasLasHormigas,
+    MejorHormiga,
+}
+
+// Longitud maxima de los segmentos que mueve Or-opt
+const LONGITUD_OR_OPT: usize = 3;
+
+fn d(distancias: &Distancias, a: Nodo, b: Nodo) -> i64 {
+    distancias[a as usize][b as usize] as i64
+}
+
+pub fn costo_camino(camino: &Camino, distancias: &Distancias) -> u32 {
+    let Some(&inicio) = camino.first() else {
+        return 0;
+    };
+    let ultimo = *camino.last().unwrap();
+    camino
+        .windows(2)
+        .map(|ventana| distancias[ventana[0] as usize][ventana[1] as usize])
+        .sum::<u32>()
+        + distancias[ultimo as usize][inicio as usize]
+}
+
+// Prueba `mover(p)` en las posiciones 0..n en orden circular a partir de
+// `cursor`. Tras una mejora el cursor queda en esa posicion, de modo que la
+// siguiente llamada retoma ahi en lugar de volver a revisar desde el inicio
+// las posiciones que ya no mejoraban.
+fn recorrer_desde(cursor: &mut usize, n: usize, mut mover: impl FnMut(usize) -> bool) -> bool {
+    for k in 0..n {
+        let p = (*cursor + k) % n;
+        if mover(p) {
+            *cursor = p;
+            return true;
+        }
+    }
+    false
+}
+
+// Or-opt: mover un segmento de 1 a 3 nodos consecutivos a otra posicion
+// del camino sin invertirlo. Devuelve true si encontro una mejora.
+pub fn or_opt(camino: &mut Camino, distancias: &Distancias, cursor: &mut usize) -> bool {
+    let n = camino.len();
+    if n < 5 {
+        return false;
+    }
+    recorrer_desde(cursor, n, |s| mover_segmento(camino, distancias, s))
+}
+
+// Or-opt de los segmentos que empiezan en la posicion s
+fn mover_segmento(camino: &mut Camino, distancias: &Distancias, s: usize) -> bool {
+    let n = camino.len();
+    for longitud in (1..=LONGITUD_OR_OPT).filter(|&longitud| s + longitud <= n) {
+        let primero = camino[s];
+        let ultimo = camino[s + longitud - 1];
+        let anterior = camino[(s + n - 1) % n];
+        let siguiente = camino[(s + longitud) % n];
+
+        // Ganancia por sacar el segmento y unir sus vecinos
+        let ganancia = d(distancias, anterior, primero) + d(distancias, ultimo, siguiente)
+            - d(distancias, anterior, siguiente);
+
+        // Insertar entre camino[g] y camino[g + 1], fuera del segmento
+        for g in 0..n {
+            let a = camino[g];
+            let b = camino[(g + 1) % n];
+            let dentro = (s..s + longitud).contains(&g)
+                || (s..s + longitud).contains(&((g + 1) % n))
+                || a == anterior;
+            if dentro {
+                continue;
+            }
+
+            let costo_insertar =
+                d(distancias, a, primero) + d(distancias, ultimo, b) - d(distancias, a, b);
+            if costo_insertar < ganancia {
+                let segmento: Camino = camino.drain(s..s + longitud).collect();
+                let posicion = camino.iter().position(|&x| x == a).unwrap() + 1;
+                camino.splice(posicion..posicion, segmento);
+                return true;
+            }
+        }
+    }
+    false
+}
+
+// 3-opt sin inversion ("segment insertion"): se cortan las aristas
+// (t[i], t[i+1]), (t[j], t[j+1]) y (t[k], t[k+1]) y se intercambian los
+// segmentos S1 = t[i+1..=j] y S2 = t[j+1..=k], quedando A S2 S1 en lugar
+// de A S1 S2. Devuelve true si encontro una mejora.
+pub fn tres_opt_segmentos(
+    camino: &mut Camino,
+    distancias: &Distancias,
+    cursor: &mut usize,
+) -> bool {
+    let n = camino.len();
+    if n < 4 {
+        return false;
+    }
+    recorrer_desde(cursor, n - 2, |i| {
+        let a = camino[i];
+        let s1_inicio = camino[i + 1];
+        for j in i + 1..n - 1 {
+            let s1_fin = camino[j];
+            let s2_inicio = camino[j + 1];
+            for k in j + 1..n {
+                let s2_fin = camino[k];
+                // Con k = n - 1 la tercera arista es la de regreso al inicio
+                let c = camino[(k + 1) % n];
+
+                let actual = d(distancias, a, s1_inicio)
+                    + d(distancias, s1_fin, s2_inicio)
+                    + d(distancias, s2_fin, c);
+                let nuevo = d(distancias, a, s2_inicio)
+                    + d(distancias, s2_fin, s1_inicio)
+                    + d(distancias, s1_fin, c);
+
+                if nuevo < actual {
+                    camino[i + 1..=k].rotate_left(j - i);
+                    return true;
+                }
+            }
+        }
+        false
+    })
+}
+
+// SOP-3-exchange (Gambardella y Dorigo): el mismo intercambio de segmentos
+// A S1 S2 -> A S2 S1, aceptado solo si ningun nodo de S2 tiene un
+// predecesor en S1. Para cada i, S1 crece con j y se marca; al extender S2
+// con k, el primer nodo con un predecesor marcado hace infactible todo k
+// mayor, asi que la busqueda sobre k se corta ahi.
+pub fn sop_3_exchange(
+    camino: &mut Camino,
+    distancias: &Distancias,
+    precedencias: &Precedencias,
+    cursor: &mut usize,
+) -> bool {
+    let n = camino.len();
+    if n < 4 {
+        return false;
+    }
+    let mut marca = vec![usize::MAX; n];
+
+    recorrer_desde(cursor, n - 2, |i| {
+        let a = camino[i];
+        let s1_inicio = camino[i + 1];
+        for j in i + 1..n - 1 {
+            let s1_fin = camino[j];
+            marca[s1_fin as usize] = i;
+            let s2_inicio = camino[j + 1];
+            for k in j + 1..n {
+                let s2_fin = camino[k];
+                let conflicto = precedencias[s2_fin as usize]
+                    .iter()
+                    .any(|&previo| marca[previo as usize] == i);
+                if conflicto {
+                    break;
+                }
+                let c = camino[(k + 1) % n];
+
+                let actual = d(distancias, a, s1_inicio)
+                    + d(distancias, s1_fin, s2_inicio)
+                    + d(distancias, s2_fin, c);
+                let nuevo = d(distancias, a, s2_inicio)
+                    + d(distancias, s2_fin, s1_inicio)
+                    + d(distancias, s1_fin, c);
+
+                if nuevo < actual {
+                    camino[i + 1..=k].rotate_left(j - i);
+                    return true;
+                }
+            }
+        }
+        false
+    })
+}
+
+// Aplica Or-opt y 3-opt sin inversion hasta llegar a un optimo local. Con
+// precedencias (SOP) esos movimientos podrian violarlas y se usa solo
+// SOP-3-exchange. Cada vecindario guarda su propio cursor.
+pub fn mejorar_camino(camino: &mut Camino, distancias: &Distancias, precedencias: &Precedencias) {
+    if precedencias.iter().any(|previos| !previos.is_empty()) {
+        let mut cursor = 0;
+        while sop_3_exchange(camino, distancias, precedencias, &mut cursor) {}
+        return;
+    }
+    let (mut cursor_or_opt, mut cursor_tres_opt) = (0, 0);
+    loop {
+        if or_opt(camino, distancias, &mut cursor_or_opt) {
+            continue;
+        }
+        if !tres_opt_segmentos(camino, distancias, &mut cursor_tres_opt) {
+            break;
+        }
+    }
+}
+
+pub fn aplicar_busqueda_local(
+    hormigas: &mut Hormigas,
+    distancias: &Distancias,
+    precedencias: &Precedencias,
+    busqueda_local: BusquedaLocal,
+) {
+    match busqueda_local {
+        BusquedaLocal::Ninguna => {}
+        BusquedaLocal::TodasLasHormigas => {
+            for hormiga in hormigas.iter_mut() {
+                mejorar_camino(&mut hormiga.camino, distancias, precedencias);
+            }
+        }
+        BusquedaLocal::MejorHormiga => {
+            if let Some(hormiga) = hormigas
+                .iter_mut()
+                .min_by_key(|hormiga| costo_camino(&hormiga.camino, distancias))
+            {
+                mejorar_camino(&mut hormiga.camino, distancias, precedencias);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sistema_hormigas::leer_matriz;
+    use rand::rngs::StdRng;
+    use rand::seq::SliceRandom;
+    use rand::{Rng, SeedableRng};
+
+    fn es_permutacion(camino: &Camino, n: usize) -> bool {
+        let mut nodos = camino.clone();
+        nodos.sort_unstable();
+        nodos == (0..n as Nodo).collect::<Camino>()
+    }
+
+    fn respeta_precedencias(camino: &Camino, precedencias: &Precedencias) -> bool {
+        let mut posicion = vec![0; camino.len()];
+        for (p, &nodo) in camino.iter().enumerate() {
+            posicion[nodo as usize] = p;
+        }
+        precedencias.iter().enumerate().all(|(nodo, previos)| {
+            previos
+                .iter()
+                .all(|&previo| posicion[previo as usize] < posicion[nodo])
+        })
+    }
+
+    // Aplica `mover` mientras mejore, comprobando en cada paso que el camino
+    // sigue siendo una permutacion y que el costo baja estrictamente
+    fn mejorar_paso_a_paso(
+        camino: &mut Camino,
+        distancias: &Distancias,
+        mut mover: impl FnMut(&mut Camino, &mut usize) -> bool,
+    ) -> usize {
+        let n = camino.len();
+        let mut cursor = 0;
+        let mut pasos = 0;
+        let mut costo = costo_camino(camino, distancias);
+        while mover(camino, &mut cursor) {
+            assert!(es_permutacion(camino, n));
+            let nuevo = costo_camino(camino, distancias);
+            assert!(nuevo < costo, "{} >= {}", nuevo, costo);
+            costo = nuevo;
+            pasos += 1;
+        }
+        pasos
+    }
+
+    #[test]
+    fn cada_movimiento_baja_el_costo() {
+        let file = leer_matriz("src/files/ftv33.txt").unwrap();
+        let n = file.matriz.len();
+        let mut generador = StdRng::seed_from_u64(11);
+        for _ in 0..5 {
+            let mut camino: Camino = (0..n as Nodo).collect();
+            camino.shuffle(&mut generador);
+            let mut otro = camino.clone();
+            let pasos = mejorar_paso_a_paso(&mut camino, &file.matriz, |camino, cursor| {
+                or_opt(camino, &file.matriz, cursor)
+            });
+            assert!(pasos > 0);
+            let pasos = mejorar_paso_a_paso(&mut otro, &file.matriz, |camino, cursor| {
+                tres_opt_segmentos(camino, &file.matriz, cursor)
+            });
+            assert!(pasos > 0);
+        }
+    }
+
+    #[test]
+    fn mejorar_camino_llega_a_un_optimo_local() {
+        let file = leer_matriz("src/files/ftv33.txt").unwrap();
+        let n = file.matriz.len();
+        let mut generador = StdRng::seed_from_u64(12);
+        for _ in 0..5 {
+            let mut camino: Camino = (0..n as Nodo).collect();
+            camino.shuffle(&mut generador);
+            let inicial = costo_camino(&camino, &file.matriz);
+            mejorar_camino(&mut camino, &file.matriz, &file.precedencias);
+            assert!(es_permutacion(&camino, n));
+            assert!(costo_camino(&camino, &file.matriz) <= inicial);
+            // Ninguna posicion de partida encuentra otra mejora
+            let mut copia = camino.clone();
+            assert!(!or_opt(&mut copia, &file.matriz, &mut 0));
+            assert!(!tres_opt_segmentos(&mut copia, &file.matriz, &mut 0));
+        }
+    }
+
+    #[test]
+    fn sop_3_exchange_respeta_precedencias() {
+        let mut generador = StdRng::seed_from_u64(13);
+        let n = 15;
+        for _ in 0..10 {
+            let distancias: Distancias = (0..n)
+                .map(|_| (0..n).map(|_| generador.random_range(1..100)).collect())
+                .collect();
+            // Precedencias al azar compatibles con el orden 0, 1, ..., n - 1
+            let mut precedencias: Precedencias = vec![Vec::new(); n];
+            for (b, previos) in precedencias.iter_mut().enumerate().skip(1) {
+                for a in 0..b {
+                    if a == 0 || generador.random_bool(0.15) {
+                        previos.push(a as Nodo);
+                    }
+                }
+            }
+            // Orden topologico al azar como camino inicial
+            let mut camino: Camino = vec![0];
+            while camino.len() < n {
+                let disponibles: Camino = (0..n as Nodo)
+                    .filter(|nodo| !camino.contains(nodo))
+                    .filter(|&nodo| {
+                        precedencias[nodo as usize]
+                            .iter()
+                            .all(|previo| camino.contains(previo))
+                    })
+                    .collect();
+                camino.push(disponibles[generador.random_range(0..disponibles.len())]);
+            }
+            assert!(respeta_precedencias(&camino, &precedencias));
+
+            mejorar_paso_a_paso(&mut camino, &distancias, |camino, cursor| {
+                let mejora = sop_3_exchange(camino, &distancias, &precedencias, cursor);
+                assert!(respeta_precedencias(camino, &precedencias));
+                mejora
+            });
+            let inicial = camino.clone();
+            mejorar_camino(&mut camino, &distancias, &precedencias);
+            assert_eq!(camino, inicial);
+        }
+    }
+}
@@ -0,0 +1,52 @@
+use crate::sistema_hormigas::{Base, Distancias, Nodo, Peso, Precedencias};
+use colonia::k_mas_cercanos;
+pub use colonia::{ListasCandidatos, Visitados};
+
+// Listas de candidatos: para cada nodo, sus k vecinos mas cercanos en
+// `conjunto_aristas`, ordenados por distancia. Las hormigas solo eligen
+// entre los candidatos no visitados y recurren al resto de los vecinos
+// cuando todos los candidatos ya se visitaron.
+
+// Se construyen una sola vez a partir de la matriz de distancias.
+// Con k = 0 o k mayor al numero de vecinos la lista es la vecindad completa.
+pub fn listas_candidatos(
+    conjunto_aristas: &Base,
+    distancias: &Distancias,
+    k: usize,
+) -> ListasCandidatos {
+    k_mas_cercanos(
+        conjunto_aristas.clone(),
+        |i, j| distancias[i][j as usize] as Peso,
+        k,
+    )
+}
+
+// Candidatos de `origen` aun no visitados y con todos sus predecesores ya
+// visitados; si no queda ninguno, todos los vecinos que cumplen lo mismo
+pub fn vertices_factibles(
+    origen: Nodo,
+    conjunto_aristas: &Base,
+    candidatos: &ListasCandidatos,
+    precedencias: &Precedencias,
+    visitados: &Visitados,
+) -> Vec<Nodo> {
+    let disponible = |j: Nodo| {
+        !visitados.contiene(j)
+            && precedencias[j as usize]
+                .iter()
+                .all(|&previo| visitados.contiene(previo))
+    };
+    let factibles: Vec<Nodo> = candidatos[origen as usize]
+        .iter()
+        .copied()
+        .filter(|&j| disponible(j))
+        .collect();
+    if !factibles.is_empty() {
+        return factibles;
+    }
+    conjunto_aristas[origen as usize]
+        .iter()
+        .copied()
+        .filter(|&j| disponible(j))
+        .collect()
+}
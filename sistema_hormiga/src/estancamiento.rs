@@ -0,0 +1,20 @@
+use crate::sistema_hormigas::{Base, Feromonas};
+
+// Criterios y acciones de estancamiento del crate `colonia`, compartidos
+// con `rutas_vehiculares`; aqui solo se leen los rastros de la matriz de
+// aristas.
+pub use colonia::estancamiento::*;
+
+pub fn diagnosticar(
+    conjunto_aristas: &Base,
+    feromonas: &Feromonas,
+    iteraciones_sin_mejora: usize,
+) -> Diagnostico {
+    let rastros = conjunto_aristas.iter().enumerate().map(|(i, vecinos)| {
+        vecinos
+            .iter()
+            .map(|&j| feromonas.get(i as u32, j))
+            .collect()
+    });
+    diagnosticar_rastros(rastros, iteraciones_sin_mejora)
+}
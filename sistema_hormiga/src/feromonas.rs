@@ -0,0 +1,161 @@
+use crate::sistema_hormigas::{
+    Base, Distancias, ImportanciaDistancia, ImportanciaFeromona, Nodo, Peso,
+};
+use std::collections::HashMap;
+
+// Fraccion minima de aristas (respecto a n * n) para usar la matriz densa
+const DENSIDAD_MINIMA: f64 = 0.5;
+
+// Valores por arista: feromona, heuristica o valor de eleccion.
+// - Densa: matriz n x n en un solo Vec por filas, sin hashing. Es la que se
+//   usa para grafos completos como las instancias ftv.
+// - Dispersa: un HashMap por nodo, para grafos con pocas aristas en `Base`.
+#[derive(Debug, Clone)]
+pub enum MatrizAristas {
+    Densa { n: usize, valores: Vec<Peso> },
+    Dispersa(Vec<HashMap<Nodo, Peso>>),
+}
+
+impl MatrizAristas {
+    // Elige la representacion segun la densidad de `conjunto_aristas` y
+    // asigna a cada arista (i, j) el valor que devuelve `valor(i, j)`
+    pub fn new(
+        conjunto_aristas: &Base,
+        mut valor: impl FnMut(Nodo, Nodo) -> Peso,
+    ) -> MatrizAristas {
+        let n = conjunto_aristas.len();
+        let total_aristas: usize = conjunto_aristas.iter().map(|vecinos| vecinos.len()).sum();
+
+        if total_aristas as f64 >= DENSIDAD_MINIMA * (n * n) as f64 {
+            let mut valores = vec![0.0; n * n];
+            for (i, vecinos) in conjunto_aristas.iter().enumerate() {
+                for &j in vecinos {
+                    valores[i * n + j as usize] = valor(i as Nodo, j);
+                }
+            }
+            MatrizAristas::Densa { n, valores }
+        } else {
+            let filas = conjunto_aristas
+                .iter()
+                .enumerate()
+                .map(|(i, vecinos)| vecinos.iter().map(|&j| (j, valor(i as Nodo, j))).collect())
+                .collect();
+            MatrizAristas::Dispersa(filas)
+        }
+    }
+
+    // Valor de la arista (i, j); 0 si la arista no existe
+    pub fn get(&self, i: Nodo, j: Nodo) -> Peso {
+        match self {
+            MatrizAristas::Densa { n, valores } => valores[i as usize * n + j as usize],
+            MatrizAristas::Dispersa(filas) => filas[i as usize].get(&j).copied().unwrap_or(0.0),
+        }
+    }
+
+    pub fn get_mut(&mut self, i: Nodo, j: Nodo) -> Option<&mut Peso> {
+        match self {
+            MatrizAristas::Densa { n, valores } => valores.get_mut(i as usize * *n + j as usize),
+            MatrizAristas::Dispersa(filas) => filas[i as usize].get_mut(&j),
+        }
+    }
+
+    // Reasigna en su lugar el valor de cada arista con `valor(i, j)`. La
+    // matriz debe haberse construido sobre el mismo `conjunto_aristas`.
+    pub fn actualizar(
+        &mut self,
+        conjunto_aristas: &Base,
+        mut valor: impl FnMut(Nodo, Nodo) -> Peso,
+    ) {
+        match self {
+            MatrizAristas::Densa { n, valores } => {
+                for (i, vecinos) in conjunto_aristas.iter().enumerate() {
+                    for &j in vecinos {
+                        valores[i * *n + j as usize] = valor(i as Nodo, j);
+                    }
+                }
+            }
+            MatrizAristas::Dispersa(filas) => {
+                for (i, fila) in filas.iter_mut().enumerate() {
+                    for (&j, peso) in fila.iter_mut() {
+                        *peso = valor(i as Nodo, j);
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn es_densa(&self) -> bool {
+        matches!(self, MatrizAristas::Densa { .. })
+    }
+}
+
+// η_ij^β con η_ij = 1 / d_ij; se calcula una sola vez por ejecucion.
+// Una distancia 0 da visibilidad infinita.
+pub fn calcular_heuristica(
+    conjunto_aristas: &Base,
+    distancias: &Distancias,
+    i_d: ImportanciaDistancia,
+) -> MatrizAristas {
+    MatrizAristas::new(conjunto_aristas, |i, j| {
+        let visibilidad_ij = 1.0 / distancias[i as usize][j as usize] as Peso;
+        visibilidad_ij.powf(i_d)
+    })
+}
+
+// Valor de eleccion τ_ij^α * η_ij^β, recalculado una vez por iteracion para
+// no evaluar `powf` en cada paso de cada hormiga. Se escribe sobre `valores`
+// (p. ej. un clon de las feromonas) para reusar la matriz entre iteraciones.
+pub fn actualizar_valores_eleccion(
+    valores: &mut MatrizAristas,
+    conjunto_aristas: &Base,
+    feromonas: &MatrizAristas,
+    heuristica: &MatrizAristas,
+    i_f: ImportanciaFeromona,
+) {
+    valores.actualizar(conjunto_aristas, |i, j| {
+        valor_eleccion(feromonas.get(i, j), heuristica.get(i, j), i_f)
+    })
+}
+
+pub fn valor_eleccion(feromona: Peso, heuristica: Peso, i_f: ImportanciaFeromona) -> Peso {
+    feromona.powf(i_f) * heuristica
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn completo(n: usize) -> Base {
+        (0..n)
+            .map(|i| (0..n).filter(|&j| j != i).map(|j| j as Nodo).collect())
+            .collect()
+    }
+
+    #[test]
+    fn actualizar_en_su_lugar_en_ambas_representaciones() {
+        // Grafo completo (densa) y un ciclo dirigido de 10 nodos (dispersa)
+        let ciclo: Base = (0..10).map(|i| vec![((i + 1) % 10) as Nodo]).collect();
+        for conjunto_aristas in [completo(4), ciclo] {
+            let feromonas = MatrizAristas::new(&conjunto_aristas, |i, j| (1 + i + j) as Peso);
+            let heuristica =
+                MatrizAristas::new(&conjunto_aristas, |i, j| 1.0 / (1 + i * j) as Peso);
+
+            let mut valores = feromonas.clone();
+            actualizar_valores_eleccion(
+                &mut valores,
+                &conjunto_aristas,
+                &feromonas,
+                &heuristica,
+                2.0,
+            );
+            assert_eq!(valores.es_densa(), feromonas.es_densa());
+            for (i, vecinos) in conjunto_aristas.iter().enumerate() {
+                for &j in vecinos {
+                    let i = i as Nodo;
+                    let esperado = valor_eleccion(feromonas.get(i, j), heuristica.get(i, j), 2.0);
+                    assert_eq!(valores.get(i, j), esperado);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,123 @@
+use crate::sistema_hormigas::{Camino, MatrizFromFile, Nodo, DISTANCIA_PROHIBIDA};
+use std::error::Error;
+
+// Solucion exacta del ATSP por programacion dinamica sobre subconjuntos
+// (Held-Karp): O(2^n * n^2) en tiempo y O(2^n * n) en memoria, por lo que
+// solo sirve para instancias pequeñas como br17. Se usa como referencia
+// para validar lo que encuentra la colonia.
+pub const MAXIMO_NODOS_HELD_KARP: usize = 20;
+
+const SIN_CAMINO: u64 = u64::MAX;
+
+// Devuelve el camino optimo (empezando en el nodo 0) y su costo
+pub fn held_karp(file: &MatrizFromFile) -> Result<(Camino, u32), Box<dyn Error>> {
+    let n = file.dimension as usize;
+    let distancias = &file.matriz;
+
+    if n == 0 {
+        return Err("La instancia no tiene nodos".into());
+    }
+    if n > MAXIMO_NODOS_HELD_KARP {
+        return Err(format!(
+            "Held-Karp solo admite hasta {} nodos ({} tiene {})",
+            MAXIMO_NODOS_HELD_KARP, file.nombre, n
+        )
+        .into());
+    }
+    if n == 1 {
+        return Ok((vec![0], 0));
+    }
+
+    let d = |i: usize, j: usize| distancias[i][j] as u64;
+
+    // El nodo 0 es el inicio fijo; los subconjuntos solo contienen 1..n,
+    // y el bit b representa al nodo b + 1
+    let m = n - 1;
+    let subconjuntos = 1usize << m;
+
+    // costo[s * m + u]: camino mas corto que sale de 0, visita exactamente
+    // los nodos de s y termina en el nodo u + 1 (u en s)
+    let mut costo = vec![SIN_CAMINO; subconjuntos * m];
+    let mut anterior = vec![u8::MAX; subconjuntos * m];
+
+    for u in 0..m {
+        costo[(1 << u) * m + u] = d(0, u + 1);
+    }
+
+    for s in 1..subconjuntos {
+        for u in 0..m {
+            if s & (1 << u) == 0 {
+                continue;
+            }
+            let actual = costo[s * m + u];
+            if actual == SIN_CAMINO {
+                continue;
+            }
+            // Extender el camino a cada nodo v que no esta en s
+            for v in 0..m {
+                if s & (1 << v) != 0 {
+                    continue;
+                }
+                let t = s | (1 << v);
+                let nuevo = actual + d(u + 1, v + 1);
+                if nuevo < costo[t * m + v] {
+                    costo[t * m + v] = nuevo;
+                    anterior[t * m + v] = u as u8;
+                }
+            }
+        }
+    }
+
+    // Cerrar el ciclo regresando a 0
+    let completo = subconjuntos - 1;
+    let (mut ultimo, costo_optimo) = (0..m)
+        .map(|u| (u, costo[completo * m + u].saturating_add(d(u + 1, 0))))
+        .min_by_key(|&(_, c)| c)
+        .unwrap();
+
+    // Cualquier camino tendria que usar una arista inexistente
+    if costo_optimo >= DISTANCIA_PROHIBIDA as u64 {
+        return Err("La instancia no tiene camino factible".into());
+    }
+
+    // Reconstruir el camino hacia atras
+    let mut camino: Camino = Vec::with_capacity(n);
+    let mut s = completo;
+    loop {
+        camino.push((ultimo + 1) as Nodo);
+        let previo = anterior[s * m + ultimo];
+        s &= !(1 << ultimo);
+        if previo == u8::MAX {
+            break;
+        }
+        ultimo = previo as usize;
+    }
+    camino.push(0);
+    camino.reverse();
+
+    Ok((camino, costo_optimo as u32))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sistema_hormigas::{costo_camino_cerrado, leer_matriz};
+
+    #[test]
+    fn optimo_de_br17() {
+        let file = leer_matriz("src/files/br17.txt").unwrap();
+        let (camino, costo) = held_karp(&file).unwrap();
+        assert_eq!(costo, 39);
+        assert_eq!(costo_camino_cerrado(&camino, &file.matriz), 39);
+
+        let mut visitados = camino.clone();
+        visitados.sort();
+        assert_eq!(visitados, (0..17).collect::<Vec<Nodo>>());
+    }
+
+    #[test]
+    fn rechaza_instancias_grandes() {
+        let file = leer_matriz("src/files/ftv33.txt").unwrap();
+        assert!(held_karp(&file).is_err());
+    }
+}
@@ -0,0 +1,445 @@
+use crate::sistema_hormigas::*;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::error::Error;
+use std::fs;
+
+// Camino mas corto de un origen a un destino en un grafo disperso (el
+// "laberinto" del planteamiento original en sistema_hormiga.py). Cada hormiga
+// camina desde el origen hasta llegar al destino, se eliminan los ciclos de
+// su recorrido y se deposita feromona sobre el camino resultante.
+
+// Pasos maximos de una hormiga, en multiplos del numero de nodos, antes de
+// darla por perdida
+const PASOS_POR_NODO: usize = 4;
+
+// Grafo no dirigido con listas de adyacencia (vecino, peso)
+#[derive(Debug, Clone)]
+pub struct GrafoLaberinto {
+    pub nombre: String,
+    pub adyacencia: Vec<Vec<(Nodo, u32)>>,
+    // Origen y destino marcados en el archivo (S y E en las cuadriculas)
+    pub origen: Option<Nodo>,
+    pub destino: Option<Nodo>,
+}
+
+impl GrafoLaberinto {
+    fn agregar_arista(&mut self, i: Nodo, j: Nodo, peso: u32) {
+        self.adyacencia[i as usize].push((j, peso));
+        self.adyacencia[j as usize].push((i, peso));
+    }
+
+    pub fn dimension(&self) -> usize {
+        self.adyacencia.len()
+    }
+
+    pub fn total_aristas(&self) -> usize {
+        self.adyacencia
+            .iter()
+            .map(|vecinos| vecinos.len())
+            .sum::<usize>()
+            / 2
+    }
+
+    fn peso(&self, i: Nodo, j: Nodo) -> Option<u32> {
+        self.adyacencia[i as usize]
+            .iter()
+            .filter(|&&(k, _)| k == j)
+            .map(|&(_, peso)| peso)
+            .min()
+    }
+
+    fn conjunto_aristas(&self) -> Base {
+        self.adyacencia
+            .iter()
+            .map(|vecinos| vecinos.iter().map(|&(j, _)| j).collect())
+            .collect()
+    }
+}
+
+fn es_cuadricula(contenido: &str) -> bool {
+    contenido
+        .lines()
+        .filter(|linea| !linea.trim().is_empty())
+        .all(|linea| linea.trim_end().chars().all(|c| "#.SE ".contains(c)))
+}
+
+// Cuadricula: '#' pared, '.' celda libre, 'S' origen, 'E' destino. Cada
+// celda es un nodo (fila * columnas + columna) conectado con peso 1 a sus
+// vecinas libres arriba, abajo, izquierda y derecha.
+fn leer_cuadricula(nombre: String, contenido: &str) -> Result<GrafoLaberinto, Box<dyn Error>> {
+    let filas: Vec<Vec<char>> = contenido
+        .lines()
+        .filter(|linea| !linea.trim().is_empty())
+        .map(|linea| linea.trim_end().chars().collect())
+        .collect();
+    let columnas = filas.iter().map(|fila| fila.len()).max().unwrap_or(0);
+    let libre = |r: usize, c: usize| filas[r].get(c).is_some_and(|&celda| celda != '#');
+    let id = |r: usize, c: usize| (r * columnas + c) as Nodo;
+
+    let mut grafo = GrafoLaberinto {
+        nombre,
+        adyacencia: vec![Vec::new(); filas.len() * columnas],
+        origen: None,
+        destino: None,
+    };
+    for (r, fila) in filas.iter().enumerate() {
+        for (c, &celda) in fila.iter().enumerate() {
+            match celda {
+                'S' => grafo.origen = Some(id(r, c)),
+                'E' => grafo.destino = Some(id(r, c)),
+                _ => {}
+            }
+            if !libre(r, c) {
+                continue;
+            }
+            if r + 1 < filas.len() && libre(r + 1, c) {
+                grafo.agregar_arista(id(r, c), id(r + 1, c), 1);
+            }
+            if libre(r, c + 1) {
+                grafo.agregar_arista(id(r, c), id(r, c + 1), 1);
+            }
+        }
+    }
+    Ok(grafo)
+}
+
+// Lista de aristas: una arista no dirigida por linea, `u v [peso]` con nodos
+// desde 0 y peso 1 si se omite. Las lineas que empiezan con '%' son
+// comentarios.
+fn leer_lista_aristas(nombre: String, contenido: &str) -> Result<GrafoLaberinto, Box<dyn Error>> {
+    let mut aristas: Vec<(Nodo, Nodo, u32)> = Vec::new();
+    for linea in contenido.lines() {
+        let linea = linea.trim();
+        if linea.is_empty() || linea.starts_with('%') {
+            continue;
+        }
+        let valores: Vec<u32> = linea
+            .split_whitespace()
+            .map(|valor| valor.parse())
+            .collect::<Result<_, _>>()
+            .map_err(|_| format!("Arista invalida: {}", linea))?;
+        match valores[..] {
+            [i, j] => aristas.push((i, j, 1)),
+            [i, j, peso] => aristas.push((i, j, peso)),
+            _ => return Err(format!("Arista invalida: {}", linea).into()),
+        }
+    }
+
+    let n = aristas
+        .iter()
+        .map(|&(i, j, _)| i.max(j) as usize + 1)
+        .max()
+        .unwrap_or(0);
+    let mut grafo = GrafoLaberinto {
+        nombre,
+        adyacencia: vec![Vec::new(); n],
+        origen: None,
+        destino: None,
+    };
+    for (i, j, peso) in aristas {
+        if i != j {
+            grafo.agregar_arista(i, j, peso);
+        }
+    }
+    Ok(grafo)
+}
+
+pub fn leer_laberinto(path: &str) -> Result<GrafoLaberinto, Box<dyn Error>> {
+    let contenido = fs::read_to_string(path)?;
+    let nombre = std::path::Path::new(path)
+        .file_stem()
+        .map_or(path.to_string(), |s| s.to_string_lossy().into_owned());
+    if es_cuadricula(&contenido) {
+        leer_cuadricula(nombre, &contenido)
+    } else {
+        leer_lista_aristas(nombre, &contenido)
+    }
+}
+
+// Dijkstra desde el origen; camino y costo minimos si el destino es alcanzable
+pub fn dijkstra(grafo: &GrafoLaberinto, origen: Nodo, destino: Nodo) -> Option<(Camino, u32)> {
+    let n = grafo.dimension();
+    let mut distancia = vec![u32::MAX; n];
+    let mut anterior: Vec<Option<Nodo>> = vec![None; n];
+    let mut cola = BinaryHeap::new();
+
+    distancia[origen as usize] = 0;
+    cola.push(Reverse((0, origen)));
+    while let Some(Reverse((d, i))) = cola.pop() {
+        if d > distancia[i as usize] {
+            continue;
+        }
+        if i == destino {
+            break;
+        }
+        for &(j, peso) in &grafo.adyacencia[i as usize] {
+            let nueva = d.saturating_add(peso);
+            if nueva < distancia[j as usize] {
+                distancia[j as usize] = nueva;
+                anterior[j as usize] = Some(i);
+                cola.push(Reverse((nueva, j)));
+            }
+        }
+    }
+
+    if distancia[destino as usize] == u32::MAX {
+        return None;
+    }
+    let mut camino = vec![destino];
+    while let Some(previo) = anterior[*camino.last().unwrap() as usize] {
+        camino.push(previo);
+    }
+    camino.reverse();
+    Some((camino, distancia[destino as usize]))
+}
+
+fn costo_recorrido(grafo: &GrafoLaberinto, camino: &Camino) -> u32 {
+    camino
+        .windows(2)
+        .map(|arista| grafo.peso(arista[0], arista[1]).unwrap_or(u32::MAX))
+        .fold(0, u32::saturating_add)
+}
+
+// Recorrido de una hormiga del origen al destino con eliminacion de ciclos:
+// al volver a un nodo del camino se descarta todo lo recorrido desde su
+// primera visita. Prefiere vecinos que no ha pisado y solo regresa cuando
+// esta en un callejon sin salida. None si no llega en el limite de pasos.
+fn recorrido_hormiga(
+    conjunto_aristas: &Base,
+    valores_eleccion: &ValoresEleccion,
+    origen: Nodo,
+    destino: Nodo,
+) -> Option<Camino> {
+    let n = conjunto_aristas.len();
+    let mut posicion = vec![usize::MAX; n];
+    let mut visitados = Visitados::new(n);
+    let mut pesos = Vec::new();
+    let mut camino: Camino = vec![origen];
+    posicion[origen as usize] = 0;
+    visitados.insertar(origen);
+
+    for _ in 0..PASOS_POR_NODO * n {
+        let actual = *camino.last().unwrap();
+        if actual == destino {
+            return Some(camino);
+        }
+
+        let vecinos = &conjunto_aristas[actual as usize];
+        let nuevos: Vec<Nodo> = vecinos
+            .iter()
+            .copied()
+            .filter(|&j| !visitados.contiene(j))
+            .collect();
+        let factibles = if nuevos.is_empty() { vecinos } else { &nuevos };
+        let siguiente = seleccion_ruleta(actual, factibles, valores_eleccion, &mut pesos)?;
+        visitados.insertar(siguiente);
+
+        let anterior = posicion[siguiente as usize];
+        if anterior != usize::MAX {
+            // Ciclo: volver a la primera visita de `siguiente`
+            for &nodo in &camino[anterior + 1..] {
+                posicion[nodo as usize] = usize::MAX;
+            }
+            camino.truncate(anterior + 1);
+        } else {
+            posicion[siguiente as usize] = camino.len();
+            camino.push(siguiente);
+        }
+    }
+    (*camino.last().unwrap() == destino).then_some(camino)
+}
+
+pub fn camino_mas_corto_hormigas(
+    configuracion: &Configuracion,
+    grafo: &GrafoLaberinto,
+    origen: Nodo,
+    destino: Nodo,
+) -> Option<(Camino, u32)> {
+    let conjunto_aristas = grafo.conjunto_aristas();
+    let p: Rho = configuracion.rho;
+
+    let mut feromonas = algoritmo_inicializacion(&conjunto_aristas);
+    // η_ij^β con η_ij = 1 / peso_ij; las aristas de peso 0 son infinitamente
+    // atractivas
+    let heuristica = MatrizAristas::new(&conjunto_aristas, |i, j| {
+        let peso = grafo.peso(i, j).unwrap_or(u32::MAX);
+        (1.0 / peso as Peso).powf(configuracion.importancia_distancia)
+    });
+
+    // Valores de eleccion con la forma de `feromonas`, recalculados en su
+    // lugar al inicio de cada iteracion
+    let mut valores_eleccion = feromonas.clone();
+
+    let mut mejor: Option<(Camino, u32)> = None;
+
+    for i in 1..=configuracion.max_iteraciones {
+        print!("\rProgreso: {}/{}", i, configuracion.max_iteraciones);
+        std::io::Write::flush(&mut std::io::stdout()).unwrap();
+
+        actualizar_valores_eleccion(
+            &mut valores_eleccion,
+            &conjunto_aristas,
+            &feromonas,
+            &heuristica,
+            configuracion.importancia_feromona,
+        );
+
+        let caminos: Vec<(Camino, u32)> = (0..configuracion.n_hormigas)
+            .filter_map(|_| {
+                recorrido_hormiga(&conjunto_aristas, &valores_eleccion, origen, destino)
+            })
+            .map(|camino| {
+                let costo = costo_recorrido(grafo, &camino);
+                (camino, costo)
+            })
+            .collect();
+
+        if let Some((camino, costo)) = caminos.iter().min_by_key(|(_, costo)| *costo) {
+            if mejor
+                .as_ref()
+                .is_none_or(|(_, mejor_costo)| costo < mejor_costo)
+            {
+                mejor = Some((camino.clone(), *costo));
+            }
+        }
+
+        // Evaporacion y deposito 1 / L_k en ambos sentidos de cada arista
+        evapozacion_feromona(&conjunto_aristas, &mut feromonas, p);
+        for (camino, costo) in &caminos {
+            let aportacion = 1.0 / (*costo).max(1) as Peso;
+            for arista in camino.windows(2) {
+                for (a, b) in [(arista[0], arista[1]), (arista[1], arista[0])] {
+                    if let Some(peso) = feromonas.get_mut(a, b) {
+                        *peso += aportacion;
+                    }
+                }
+            }
+        }
+    }
+    println!();
+    mejor
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Cuadricula de 7 x 9 con dos pasillos de S a E: por la izquierda y
+    // abajo (10 pasos) o serpenteando por arriba (14 pasos)
+    const CUADRICULA: &str = "src/files/laberinto.txt";
+    const ARISTAS: &str = "src/files/laberinto_aristas.txt";
+
+    fn sin_repetidos(camino: &Camino) -> bool {
+        let mut vistos = camino.clone();
+        vistos.sort_unstable();
+        vistos.dedup();
+        vistos.len() == camino.len()
+    }
+
+    fn es_recorrido_valido(grafo: &GrafoLaberinto, camino: &Camino) -> bool {
+        camino
+            .windows(2)
+            .all(|arista| grafo.peso(arista[0], arista[1]).is_some())
+    }
+
+    #[test]
+    fn lee_la_cuadricula_con_origen_y_destino() {
+        let grafo = leer_laberinto(CUADRICULA).unwrap();
+        assert_eq!(grafo.nombre, "laberinto");
+        assert_eq!(grafo.dimension(), 7 * 9);
+        // 24 celdas libres unidas por 12 aristas horizontales y 12 verticales
+        assert_eq!(grafo.total_aristas(), 24);
+        assert_eq!(grafo.origen, Some(9 + 1));
+        assert_eq!(grafo.destino, Some(5 * 9 + 7));
+        assert_eq!(grafo.peso(10, 11), Some(1));
+        assert_eq!(grafo.peso(10, 1), None);
+    }
+
+    #[test]
+    fn lee_la_lista_de_aristas() {
+        let grafo = leer_laberinto(ARISTAS).unwrap();
+        assert_eq!(grafo.dimension(), 7);
+        assert_eq!(grafo.total_aristas(), 9);
+        assert_eq!((grafo.origen, grafo.destino), (None, None));
+        assert_eq!(grafo.peso(2, 4), Some(3));
+        assert_eq!(grafo.peso(4, 2), Some(3));
+        // Sin peso explicito la arista pesa 1
+        assert_eq!(grafo.peso(6, 5), Some(1));
+
+        assert!(leer_lista_aristas("x".into(), "0 1 2 3").is_err());
+        assert!(leer_lista_aristas("x".into(), "0 a").is_err());
+    }
+
+    #[test]
+    fn dijkstra_da_el_costo_minimo() {
+        let grafo = leer_laberinto(CUADRICULA).unwrap();
+        let (camino, costo) = dijkstra(&grafo, 10, 52).unwrap();
+        assert_eq!(costo, 10);
+        assert_eq!(camino.len(), 11);
+        assert!(es_recorrido_valido(&grafo, &camino));
+
+        let grafo = leer_laberinto(ARISTAS).unwrap();
+        assert_eq!(dijkstra(&grafo, 0, 5), Some((vec![0, 1, 2, 4, 3, 5], 8)));
+    }
+
+    #[test]
+    fn dijkstra_sin_camino() {
+        let grafo = leer_cuadricula("x".into(), "S#E").unwrap();
+        assert_eq!(dijkstra(&grafo, 0, 2), None);
+    }
+
+    #[test]
+    fn recorrido_hormiga_elimina_los_ciclos() {
+        let grafo = leer_laberinto(CUADRICULA).unwrap();
+        let conjunto_aristas = grafo.conjunto_aristas();
+        let feromonas = algoritmo_inicializacion(&conjunto_aristas);
+        let heuristica = MatrizAristas::new(&conjunto_aristas, |_, _| 1.0);
+        let mut valores_eleccion = feromonas.clone();
+        actualizar_valores_eleccion(
+            &mut valores_eleccion,
+            &conjunto_aristas,
+            &feromonas,
+            &heuristica,
+            1.0,
+        );
+
+        fijar_semilla(3);
+        let mut llegadas = 0;
+        for _ in 0..200 {
+            let Some(camino) = recorrido_hormiga(&conjunto_aristas, &valores_eleccion, 10, 52)
+            else {
+                continue;
+            };
+            llegadas += 1;
+            assert_eq!(camino.first(), Some(&10));
+            assert_eq!(camino.last(), Some(&52));
+            assert!(sin_repetidos(&camino), "{:?}", camino);
+            assert!(es_recorrido_valido(&grafo, &camino));
+        }
+        assert!(llegadas > 0);
+    }
+
+    #[test]
+    fn hormigas_alcanzan_el_costo_de_dijkstra() {
+        let configuracion = Configuracion {
+            n_hormigas: 10,
+            importancia_feromona: 1.0,
+            importancia_distancia: 2.0,
+            max_iteraciones: 30,
+            ..Configuracion::default()
+        };
+        for (archivo, origen, destino) in [(CUADRICULA, 10, 52), (ARISTAS, 0, 5)] {
+            let grafo = leer_laberinto(archivo).unwrap();
+            let (_, optimo) = dijkstra(&grafo, origen, destino).unwrap();
+
+            fijar_semilla(1);
+            let (camino, costo) =
+                camino_mas_corto_hormigas(&configuracion, &grafo, origen, destino).unwrap();
+            assert_eq!(costo, optimo, "{}", archivo);
+            assert_eq!(costo_recorrido(&grafo, &camino), costo);
+            assert_eq!((camino[0], *camino.last().unwrap()), (origen, destino));
+            assert!(sin_repetidos(&camino), "{:?}", camino);
+        }
+    }
+}
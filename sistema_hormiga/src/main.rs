@@ -1,36 +1,178 @@
+mod biobjetivo;
+mod busqueda_local;
+mod candidatos;
+mod estancamiento;
+mod feromonas;
+mod held_karp;
+mod laberinto;
+mod ramificacion_acotamiento;
 mod sistema_hormigas;
+mod tsplib;
+mod ventanas_tiempo;
 
+use crate::biobjetivo::*;
+use crate::held_karp::{held_karp, MAXIMO_NODOS_HELD_KARP};
+use crate::laberinto::*;
+use crate::ramificacion_acotamiento::*;
 use crate::sistema_hormigas::*;
+use crate::ventanas_tiempo::*;
 use std::env;
+use std::time::Duration;
 
+// Devuelve el mejor camino y su costo, o None si ninguna hormiga completo
+// un camino
 fn sistema_hormigas(
-    n_hormigas: usize,
-    _importancia_distancia: Rho,
-    _importancia_feromona: Rho,
-    max_iteraciones: usize,
+    configuracion: &Configuracion,
     filepath: String,
-) -> Result<(), Box<dyn std::error::Error>> {
+) -> Result<Option<(Camino, u32)>, Box<dyn std::error::Error>> {
     let file = leer_matriz(filepath.as_str())?;
-    let conjunto_aristas = get_conjunto_aristas_from_distancia(&file);
-    let distancias = file.matriz;
+    println!(
+        "Instancia: {} ({}, {} nodos)",
+        file.nombre, file.tipo, file.dimension
+    );
+    let conjunto_aristas = get_conjunto_aristas_from_distancia(&file, &configuracion.centinelas);
+    let es_sop = file.es_sop();
+    let mut distancias = file.matriz;
+    prohibir_aristas_inexistentes(&conjunto_aristas, &mut distancias);
+
+    let n_hormigas = configuracion.n_hormigas;
+    let max_iteraciones = configuracion.max_iteraciones;
+    let p: Rho = configuracion.rho;
+
+    let n = file.dimension as usize;
+    let costo_vecino = costo_vecino_mas_cercano(&conjunto_aristas, &distancias, 0).max(1);
+
+    // τ0 = 1 / (n * L_vecino_mas_cercano), usado por ACS
+    let tau0: Peso = 1.0 / (n as Peso * costo_vecino as Peso);
+
+    // Limites de MMAS, estimados con el vecino mas cercano hasta tener un camino
+    let mut limites = LimitesFeromona::new(costo_vecino, p, n, configuracion.p_mejor);
+
+    // Rastros iniciales, tambien usados al reiniciar por estancamiento
+    let feromonas_iniciales = |limites: &LimitesFeromona| match configuracion.modo {
+        Modo::SistemaHormigas => algoritmo_inicializacion(&conjunto_aristas),
+        Modo::SistemaColonia => inicializacion_uniforme(&conjunto_aristas, tau0),
+        Modo::MaxMin => inicializacion_uniforme(&conjunto_aristas, limites.maximo),
+    };
+    let mut feromonas = feromonas_iniciales(&limites);
+
+    // η_ij^β no cambia durante la ejecucion
+    let heuristica = calcular_heuristica(
+        &conjunto_aristas,
+        &distancias,
+        configuracion.importancia_distancia,
+    );
+
+    // k vecinos mas cercanos de cada nodo, fijos durante la ejecucion
+    let candidatos = listas_candidatos(&conjunto_aristas, &distancias, configuracion.n_candidatos);
 
-    let p: Rho = 0.3;
+    println!(
+        "Feromonas: matriz {}",
+        if feromonas.es_densa() {
+            "densa"
+        } else {
+            "dispersa"
+        }
+    );
 
-    let mut feromonas = algoritmo_inicializacion(&conjunto_aristas);
+    // Valores de eleccion con la forma de `feromonas`, recalculados en su
+    // lugar al inicio de cada iteracion
+    let mut valores_eleccion = feromonas.clone();
 
     let mut camino_minimo: Camino = Vec::new();
     let mut mejor_costo = u32::MAX;
+    let mut iteraciones_sin_mejora = 0;
+
+    // ρ vigente: cambia si el estancamiento aumenta la evaporacion
+    let mut rho_actual = p;
+    let criterio = configuracion.estancamiento;
+    let mut iteraciones_desde_accion = 0;
+    let mut acciones_estancamiento = 0;
 
     for i in 1..=max_iteraciones {
-        print!("\rProgreso: {}/{}", i, max_iteraciones);
+        let diagnostico = diagnosticar(&conjunto_aristas, &feromonas, iteraciones_sin_mejora);
+        print!(
+            "\rProgreso: {}/{} ({})   ",
+            i,
+            max_iteraciones,
+            diagnostico.resumen()
+        );
         std::io::Write::flush(&mut std::io::stdout()).unwrap();
 
-        let ciudad_inicio = get_inicio(&file.dimension);
+        iteraciones_desde_accion += 1;
+        if criterio.estancado(&diagnostico, iteraciones_desde_accion) {
+            acciones_estancamiento += 1;
+            iteraciones_desde_accion = 0;
+            match criterio.accion {
+                AccionEstancamiento::Ninguna => {}
+                AccionEstancamiento::Reiniciar => feromonas = feromonas_iniciales(&limites),
+                AccionEstancamiento::AumentarEvaporacion => {
+                    rho_actual = aumentar_evaporacion(rho_actual)
+                }
+                AccionEstancamiento::Detener => {
+                    println!();
+                    println!("Colonia estancada: se detiene en la iteracion {}", i);
+                    break;
+                }
+            }
+        }
+
+        // En SOP todos los caminos empiezan en el nodo 0
+        let ciudad_inicio = if es_sop {
+            0
+        } else {
+            get_inicio(&file.dimension)
+        };
         let mut hormigas = inicializacion_hormigas(n_hormigas, ciudad_inicio);
 
-        construccion_caminos(&conjunto_aristas, &feromonas, &mut hormigas, &distancias);
+        // τ_ij^α * η_ij^β de todas las aristas para esta iteracion
+        actualizar_valores_eleccion(
+            &mut valores_eleccion,
+            &conjunto_aristas,
+            &feromonas,
+            &heuristica,
+            configuracion.importancia_feromona,
+        );
+
+        match configuracion.modo {
+            Modo::SistemaHormigas | Modo::MaxMin => construccion_caminos(
+                &conjunto_aristas,
+                &candidatos,
+                &file.precedencias,
+                &valores_eleccion,
+                &mut hormigas,
+            ),
+            Modo::SistemaColonia => construccion_caminos_acs(
+                &conjunto_aristas,
+                &candidatos,
+                &file.precedencias,
+                &mut feromonas,
+                &mut valores_eleccion,
+                &heuristica,
+                &mut hormigas,
+                configuracion.importancia_feromona,
+                configuracion.q0,
+                configuracion.xi,
+                tau0,
+            ),
+        }
+
+        // Solo los caminos completos son soluciones
+        hormigas.retain(|hormiga| camino_completo(&conjunto_aristas, &hormiga.camino));
+        if hormigas.is_empty() {
+            iteraciones_sin_mejora += 1;
+            continue;
+        }
+
+        // Post-optimizacion de los caminos construidos
+        aplicar_busqueda_local(
+            &mut hormigas,
+            &distancias,
+            &file.precedencias,
+            configuracion.busqueda_local,
+        );
 
-        let evaluacion_caminos = evaluacion_caminos(&hormigas, hormigas.len(), &distancias);
+        let evaluacion_caminos = evaluacion_caminos(&hormigas, hormigas.len(), &distancias)?;
 
         // Mostrar el camino de cada hormiga
         // for (i, hormiga) in hormigas.iter().enumerate() {
@@ -47,57 +189,649 @@ fn sistema_hormigas(
         if *costo_actual < mejor_costo {
             mejor_costo = *costo_actual;
             camino_minimo = hormigas[pos_mejor].camino.clone();
+            iteraciones_sin_mejora = 0;
+            rho_actual = p;
+            limites = LimitesFeromona::new(mejor_costo, p, n, configuracion.p_mejor);
+        } else {
+            iteraciones_sin_mejora += 1;
         }
 
-        evapozacion_feromona(&conjunto_aristas, &mut feromonas, p);
+        match configuracion.modo {
+            Modo::SistemaHormigas => {
+                evapozacion_feromona(&conjunto_aristas, &mut feromonas, rho_actual);
+
+                actualizacion_feromona_estrategia(
+                    configuracion,
+                    &hormigas,
+                    &mut feromonas,
+                    &evaluacion_caminos,
+                    &camino_minimo,
+                    mejor_costo,
+                );
+            }
+            Modo::SistemaColonia => {
+                actualizacion_global_acs(&camino_minimo, mejor_costo, &mut feromonas, rho_actual);
+            }
+            Modo::MaxMin => {
+                evapozacion_feromona(&conjunto_aristas, &mut feromonas, rho_actual);
+
+                // Alternar entre la mejor de la iteracion y la mejor global
+                let (camino, costo) = if i % configuracion.frecuencia_mejor_global.max(1) == 0 {
+                    (&camino_minimo, mejor_costo)
+                } else {
+                    (&hormigas[pos_mejor].camino, *costo_actual)
+                };
+                actualizacion_feromona_mmas(camino, costo, &mut feromonas, &limites);
+                limitar_feromona(&conjunto_aristas, &mut feromonas, &limites);
 
-        actualizacion_feromona(&hormigas, &mut feromonas, &evaluacion_caminos);
+                // Estancamiento: reiniciar los rastros a τmax
+                if iteraciones_sin_mejora >= configuracion.iteraciones_reinicio {
+                    feromonas = inicializacion_uniforme(&conjunto_aristas, limites.maximo);
+                    iteraciones_sin_mejora = 0;
+                }
+            }
+        }
     }
     println!();
+    if criterio.accion != AccionEstancamiento::Ninguna {
+        println!(
+            "Acciones por estancamiento ({:?}): {}",
+            criterio.accion, acciones_estancamiento
+        );
+    }
+    if camino_minimo.is_empty() {
+        println!("Ninguna hormiga construyo un camino factible");
+        return Ok(None);
+    }
     println!("Camino minimo encontrado: {:?}", camino_minimo);
     println!("Costo del camino minimo: {}", mejor_costo);
+    if let Some(mejor_conocido) = file.mejor_conocido {
+        println!("Mejor valor conocido: {}", mejor_conocido);
+        println!("Gap: {:.2}%", gap_porcentual(mejor_costo, mejor_conocido));
+    }
+
+    // Cota inferior propia para un gap que no depende de valores publicados
+    if configuracion.tiempo_ramificacion > 0.0 {
+        let resultado = ramificacion_y_acotamiento(
+            &distancias,
+            Some(&camino_minimo),
+            Duration::from_secs_f64(configuracion.tiempo_ramificacion),
+        );
+        imprimir_ramificacion(&resultado);
+        println!(
+            "Gap certificado: {:.2}%",
+            gap_porcentual(mejor_costo, resultado.cota_inferior)
+        );
+        // Ramificacion parte del camino de la colonia y solo lo mejora. En SOP
+        // ignora las precedencias: su cota sigue siendo valida, su camino no.
+        if let (Some(camino), Some(costo)) = (resultado.camino, resultado.costo) {
+            if costo < mejor_costo && !es_sop {
+                camino_minimo = camino;
+                mejor_costo = costo;
+            }
+        }
+    }
+
+    if let Some(archivo_tour) = &configuracion.archivo_tour {
+        escribir_tour(archivo_tour, &file.nombre, &camino_minimo, mejor_costo)?;
+        println!("Camino guardado en {}", archivo_tour);
+    }
+    Ok(Some((camino_minimo, mejor_costo)))
+}
+
+// Costo y validez de un camino guardado en formato .tour. Las aristas con
+// peso centinela no existen, igual que al construir los caminos.
+fn evaluar_tour(
+    instancia: &str,
+    tour: &str,
+    centinelas: &[u32],
+) -> Result<(), Box<dyn std::error::Error>> {
+    let file = leer_matriz(instancia)?;
+    println!(
+        "Instancia: {} ({}, {} nodos)",
+        file.nombre, file.tipo, file.dimension
+    );
+    let camino = leer_tour(tour)?;
+    let conjunto_aristas = get_conjunto_aristas_from_distancia(&file, centinelas);
+
+    match validar_camino(&conjunto_aristas, &file.precedencias, &camino) {
+        Ok(()) => println!("Valido: si"),
+        Err(e) => {
+            println!("Valido: no ({})", e);
+            return Ok(());
+        }
+    }
+    let costo = costo_camino_cerrado(&camino, &file.matriz);
+    println!("Costo: {}", costo);
+    if let Some(mejor_conocido) = file.mejor_conocido {
+        println!("Mejor valor conocido: {}", mejor_conocido);
+        println!(
+            "Gap: {:.2}%",
+            gap_porcentual(costo.min(u32::MAX as u64) as u32, mejor_conocido)
+        );
+    }
+    Ok(())
+}
+
+fn imprimir_ramificacion(resultado: &ResultadoRamificacion) {
+    println!(
+        "Ramificacion y acotamiento: {} nodos ({})",
+        resultado.nodos_explorados,
+        if resultado.optimo {
+            "completo"
+        } else {
+            "tiempo agotado"
+        }
+    );
+    if let (Some(camino), Some(costo)) = (&resultado.camino, resultado.costo) {
+        println!("Mejor camino de ramificacion: {:?}", camino);
+        println!("Costo de ramificacion: {}", costo);
+    }
+    println!("Cota inferior: {}", resultado.cota_inferior);
+}
+
+// Optimo exacto como referencia para validar la colonia: Held-Karp para
+// instancias pequeñas y ramificacion y acotamiento con tiempo limite para
+// las demas
+fn solucion_exacta(
+    filepath: &str,
+    tiempo_limite: Duration,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut file = leer_matriz(filepath)?;
+    println!(
+        "Instancia: {} ({}, {} nodos)",
+        file.nombre, file.tipo, file.dimension
+    );
+    if file.es_sop() {
+        return Err("Los metodos exactos no admiten restricciones de precedencia (SOP)".into());
+    }
+    let conjunto_aristas = get_conjunto_aristas_from_distancia(&file, &[]);
+    prohibir_aristas_inexistentes(&conjunto_aristas, &mut file.matriz);
+
+    let costo = if file.dimension as usize <= MAXIMO_NODOS_HELD_KARP {
+        let (camino, costo) = held_karp(&file)?;
+        println!("Camino optimo: {:?}", camino);
+        println!("Costo optimo: {}", costo);
+        costo
+    } else {
+        let resultado = ramificacion_y_acotamiento(&file.matriz, None, tiempo_limite);
+        imprimir_ramificacion(&resultado);
+        if !resultado.optimo {
+            return Ok(());
+        }
+        resultado
+            .costo
+            .filter(|&costo| costo < DISTANCIA_PROHIBIDA)
+            .ok_or("La instancia no tiene camino factible")?
+    };
+
+    if let Some(mejor_conocido) = file.mejor_conocido {
+        println!("Mejor valor conocido: {}", mejor_conocido);
+        if mejor_conocido != costo {
+            return Err("El optimo no coincide con el mejor valor conocido".into());
+        }
+    }
+    Ok(())
+}
+
+// Camino mas corto entre dos nodos de un laberinto, comparado con Dijkstra
+fn camino_laberinto(
+    configuracion: &Configuracion,
+    filepath: &str,
+    extremos: Option<(Nodo, Nodo)>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let grafo = leer_laberinto(filepath)?;
+    println!(
+        "Laberinto: {} ({} nodos, {} aristas)",
+        grafo.nombre,
+        grafo.dimension(),
+        grafo.total_aristas()
+    );
+
+    let (origen, destino) = match (extremos, grafo.origen, grafo.destino) {
+        (Some(extremos), _, _) => extremos,
+        (None, Some(origen), Some(destino)) => (origen, destino),
+        _ => return Err("Faltan <nodo_inicio> y <nodo_destino>".into()),
+    };
+    if origen as usize >= grafo.dimension() || destino as usize >= grafo.dimension() {
+        return Err(format!("Nodo fuera del grafo (0..{})", grafo.dimension()).into());
+    }
+    println!("Origen: {}, destino: {}", origen, destino);
+
+    let resultado = camino_mas_corto_hormigas(configuracion, &grafo, origen, destino);
+    match &resultado {
+        Some((camino, costo)) => {
+            println!("Camino minimo encontrado: {:?}", camino);
+            println!("Costo del camino minimo: {}", costo);
+        }
+        None => println!("Ninguna hormiga llego al destino"),
+    }
+
+    match (dijkstra(&grafo, origen, destino), resultado) {
+        (Some((_, optimo)), Some((_, costo))) => {
+            println!("Costo Dijkstra: {}", optimo);
+            println!("Gap: {:.2}%", gap_porcentual(costo, optimo));
+        }
+        (Some((_, optimo)), None) => println!("Costo Dijkstra: {}", optimo),
+        (None, _) => println!("Dijkstra: el destino no es alcanzable"),
+    }
+    Ok(())
+}
+
+// TSP con ventanas de tiempo en formato de Dumas / Gendreau
+fn camino_ventanas(
+    configuracion: &Configuracion,
+    filepath: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let instancia = leer_ventanas(filepath)?;
+    println!(
+        "Instancia: {} (TSPTW, {} nodos)",
+        instancia.nombre,
+        instancia.distancias.len()
+    );
+
+    let Some((camino, evaluacion)) = sistema_hormigas_ventanas(configuracion, &instancia) else {
+        println!("Ninguna hormiga construyo un camino completo");
+        return Ok(());
+    };
+    println!("Camino minimo encontrado: {:?}", camino);
+    println!("Costo del camino minimo: {}", evaluacion.costo);
+    println!("Makespan: {}", evaluacion.makespan);
+    println!(
+        "Tardanza: {} ({} ciudades fuera de su ventana)",
+        evaluacion.tardanza, evaluacion.ciudades_tarde
+    );
+    println!(
+        "Factible: {}",
+        if evaluacion.es_factible() { "si" } else { "no" }
+    );
+    Ok(())
+}
+
+// ATSP biobjetivo: frente de Pareto sobre dos matrices de pesos
+fn frente_biobjetivo(
+    configuracion: &Configuracion,
+    primera: &str,
+    segunda: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let primera = leer_matriz(primera)?;
+    let segunda = leer_matriz(segunda)?;
+    if primera.dimension != segunda.dimension {
+        return Err(format!(
+            "Las instancias tienen dimension distinta: {} ({}) y {} ({})",
+            primera.nombre, primera.dimension, segunda.nombre, segunda.dimension
+        )
+        .into());
+    }
+    if primera.es_sop() || segunda.es_sop() {
+        return Err("El modo biobjetivo no admite instancias SOP".into());
+    }
+    println!(
+        "Instancias: {} y {} ({} nodos)",
+        primera.nombre, segunda.nombre, primera.dimension
+    );
+
+    let archivo = sistema_hormigas_biobjetivo(configuracion, &primera, &segunda);
+    if archivo.is_empty() {
+        println!("Ninguna hormiga construyo un camino factible");
+        return Ok(());
+    }
+    println!("Frente de Pareto ({} soluciones):", archivo.len());
+    for solucion in &archivo {
+        println!(
+            "  {} {}: {:?}",
+            solucion.costos[0], solucion.costos[1], solucion.camino
+        );
+    }
+    let referencia = configuracion
+        .referencia_hipervolumen
+        .unwrap_or_else(|| referencia_nadir(&archivo));
+    println!(
+        "Hipervolumen (referencia {}, {}): {:.1}",
+        referencia.0,
+        referencia.1,
+        hipervolumen(&archivo, referencia)
+    );
+    Ok(())
+}
+
+// Opciones adicionales despues de los argumentos posicionales: --clave valor
+fn leer_opciones(opciones: &[String], configuracion: &mut Configuracion) -> Result<(), String> {
+    let mut iter = opciones.iter();
+    while let Some(clave) = iter.next() {
+        let valor = iter
+            .next()
+            .ok_or(format!("Falta el valor de la opcion {}", clave))?;
+        let numero = || {
+            valor
+                .parse::<f64>()
+                .map_err(|_| format!("Valor invalido para {}: {}", clave, valor))
+        };
+        let entero = || {
+            valor
+                .parse::<usize>()
+                .map_err(|_| format!("Valor invalido para {}: {}", clave, valor))
+        };
+        match clave.as_str() {
+            "--modo" => {
+                configuracion.modo = match valor.as_str() {
+                    "as" => Modo::SistemaHormigas,
+                    "acs" => Modo::SistemaColonia,
+                    "mmas" => Modo::MaxMin,
+                    _ => return Err(format!("Modo desconocido: {} (as | acs | mmas)", valor)),
+                }
+            }
+            "--actualizacion" => {
+                configuracion.estrategia = match valor.as_str() {
+                    "todas" => EstrategiaActualizacion::TodasLasHormigas,
+                    "elitista" => EstrategiaActualizacion::Elitista,
+                    "rango" => EstrategiaActualizacion::Rango,
+                    "mejor-iteracion" => EstrategiaActualizacion::MejorIteracion,
+                    _ => {
+                        return Err(format!(
+                    "Actualizacion desconocida: {} (todas | elitista | rango | mejor-iteracion)",
+                    valor
+                ))
+                    }
+                }
+            }
+            "--peso-elitista" => configuracion.peso_elitista = numero()?,
+            "--w-rango" => configuracion.hormigas_rango = entero()?,
+            "--rho" => configuracion.rho = numero()?,
+            "--q0" => configuracion.q0 = numero()?,
+            "--xi" => configuracion.xi = numero()?,
+            "--p-mejor" => configuracion.p_mejor = numero()?,
+            "--frecuencia-global" => configuracion.frecuencia_mejor_global = entero()?,
+            "--reinicio" => configuracion.iteraciones_reinicio = entero()?,
+            "--candidatos" => configuracion.n_candidatos = entero()?,
+            "--cota-exacta" => configuracion.tiempo_ramificacion = numero()?,
+            "--tour-out" => configuracion.archivo_tour = Some(valor.clone()),
+            "--semilla" => {
+                configuracion.semilla = Some(
+                    valor
+                        .parse::<u64>()
+                        .map_err(|_| format!("Valor invalido para {}: {}", clave, valor))?,
+                )
+            }
+            "--centinela" => configuracion.centinelas.push(
+                valor
+                    .parse::<u32>()
+                    .map_err(|_| format!("Valor invalido para {}: {}", clave, valor))?,
+            ),
+            "--estancamiento" => {
+                configuracion.estancamiento.accion = match valor.as_str() {
+                    "ninguna" => AccionEstancamiento::Ninguna,
+                    "reiniciar" => AccionEstancamiento::Reiniciar,
+                    "detener" => AccionEstancamiento::Detener,
+                    "evaporar" => AccionEstancamiento::AumentarEvaporacion,
+                    _ => {
+                        return Err(format!(
+                            "Accion de estancamiento desconocida: {} (ninguna | reiniciar | detener | evaporar)",
+                            valor
+                        ))
+                    }
+                }
+            }
+            "--sin-mejora" => configuracion.estancamiento.iteraciones_sin_mejora = entero()?,
+            "--umbral-ramificacion" => {
+                configuracion.estancamiento.umbral_ramificacion = numero()?
+            }
+            "--umbral-entropia" => configuracion.estancamiento.umbral_entropia = numero()?,
+            "--referencia" => {
+                let punto = valor
+                    .split(',')
+                    .map(|v| v.trim().parse::<f64>())
+                    .collect::<Result<Vec<f64>, _>>();
+                configuracion.referencia_hipervolumen = match punto.as_deref() {
+                    Ok(&[f1, f2]) => Some((f1, f2)),
+                    _ => return Err(format!("Referencia invalida: {} (f1,f2)", valor)),
+                }
+            }
+            "--busqueda-local" => {
+                configuracion.busqueda_local = match valor.as_str() {
+                    "ninguna" => BusquedaLocal::Ninguna,
+                    "todas" => BusquedaLocal::TodasLasHormigas,
+                    "mejor" => BusquedaLocal::MejorHormiga,
+                    _ => {
+                        return Err(format!(
+                            "Busqueda local desconocida: {} (ninguna | todas | mejor)",
+                            valor
+                        ))
+                    }
+                }
+            }
+            _ => return Err(format!("Opcion desconocida: {}", clave)),
+        }
+    }
+
+    if configuracion.tiempo_ramificacion < 0.0 {
+        return Err("cota-exacta debe ser >= 0".into());
+    }
+    if !(0.0..=1.0).contains(&configuracion.rho) {
+        return Err("rho debe estar en [0, 1]".into());
+    }
+    if !(0.0..=1.0).contains(&configuracion.q0) {
+        return Err("q0 debe estar en [0, 1]".into());
+    }
+    if !(0.0..=1.0).contains(&configuracion.xi) {
+        return Err("xi debe estar en [0, 1]".into());
+    }
+    if !(configuracion.p_mejor > 0.0 && configuracion.p_mejor < 1.0) {
+        return Err("p-mejor debe estar en (0, 1)".into());
+    }
     Ok(())
 }
 
 fn main() {
-    let args: Vec<String> = env::args().collect();
+    let mut args: Vec<String> = env::args().collect();
+
+    if (args.len() == 3 || args.len() == 4) && args[1] == "exacto" {
+        let segundos: f64 = match args.get(3).map(|s| s.parse()) {
+            None => 60.0,
+            Some(Ok(segundos)) => segundos,
+            Some(Err(_)) => {
+                eprintln!("Tiempo limite invalido: {}", args[3]);
+                std::process::exit(1);
+            }
+        };
+        if let Err(e) = solucion_exacta(&args[2], Duration::from_secs_f64(segundos.max(0.0))) {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
 
-    if args.len() < 5 {
+    if args.len() >= 4 && args[1] == "evaluate-tour" {
+        let mut configuracion = Configuracion::default();
+        if let Err(e) = leer_opciones(&args[4..], &mut configuracion) {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+        if let Err(e) = evaluar_tour(&args[2], &args[3], &configuracion.centinelas) {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    // Modo laberinto: mismos argumentos posicionales, mas origen y destino
+    let modo_laberinto = args.get(1).is_some_and(|a| a == "laberinto");
+    // Modo ventanas: TSPTW con los mismos argumentos posicionales
+    let modo_ventanas = args.get(1).is_some_and(|a| a == "ventanas");
+    // Modo biobjetivo: una segunda matriz despues de <filepath>
+    let modo_biobjetivo = args.get(1).is_some_and(|a| a == "biobjetivo");
+    if modo_laberinto || modo_ventanas || modo_biobjetivo {
+        args.remove(1);
+    }
+
+    if args.len() < 6 {
         eprintln!(
-            "Uso: $ ./{} <n_hormigas> <nodo_inicio> <nodo_destino> <iteraciones_maximas> <filepath>",
+            "Uso: $ ./{} <n_hormigas> <importancia_feromona> <importancia_distancia> <iteraciones_maximas> <filepath> [--modo as|acs|mmas] [--actualizacion todas|elitista|rango|mejor-iteracion] [--peso-elitista e] [--w-rango w] [--rho r] [--q0 q] [--xi x] [--p-mejor p] [--frecuencia-global k] [--reinicio n] [--busqueda-local ninguna|todas|mejor] [--candidatos k] [--cota-exacta segundos] [--centinela peso]... [--estancamiento ninguna|reiniciar|detener|evaporar] [--sin-mejora n] [--umbral-ramificacion b] [--umbral-entropia h] [--tour-out archivo.tour] [--semilla s]",
             args[0]
         );
-        return;
+        eprintln!("       $ ./{} exacto <filepath> [segundos]", args[0]);
+        eprintln!(
+            "       $ ./{} evaluate-tour <filepath> <tour> [--centinela peso]...",
+            args[0]
+        );
+        eprintln!("       $ ./{} ventanas <n_hormigas> <importancia_feromona> <importancia_distancia> <iteraciones_maximas> <filepath> [--rho r]", args[0]);
+        eprintln!("       $ ./{} biobjetivo <n_hormigas> <importancia_feromona> <importancia_distancia> <iteraciones_maximas> <filepath> <filepath2> [--rho r] [--candidatos k] [--centinela peso]... [--referencia f1,f2]", args[0]);
+        eprintln!("       $ ./{} laberinto <n_hormigas> <importancia_feromona> <importancia_distancia> <iteraciones_maximas> <filepath> [<nodo_inicio> <nodo_destino>] [--rho r]", args[0]);
+        std::process::exit(1);
     }
     let n_hormigas: usize = args[1].parse().expect("Numero de hormigas invalido");
     if n_hormigas < 1 {
         eprintln!("El numero de hormigas debe ser al menos 1");
-        return;
+        std::process::exit(1);
     }
-    let importancia_feromona: Rho = args[2].parse().expect("Numero de nodo invalido");
+    let importancia_feromona: ImportanciaFeromona = args[2]
+        .parse()
+        .expect("Importancia de la feromona invalida");
     if importancia_feromona < 0.0 {
-        eprintln!("Verificar el ejemplo y sus nodos disponibles");
-        return;
+        eprintln!("La importancia de la feromona debe ser >= 0");
+        std::process::exit(1);
     }
-    let importancia_distancia: Rho = args[3].parse().expect("Numero de nodo invalido");
+    let importancia_distancia: ImportanciaDistancia = args[3]
+        .parse()
+        .expect("Importancia de la distancia invalida");
     if importancia_distancia < 0.0 {
-        eprintln!("Verificar el ejemplo y sus nodos disponibles");
-        return;
+        eprintln!("La importancia de la distancia debe ser >= 0");
+        std::process::exit(1);
     }
     let iteraciones: usize = args[4].parse().expect("Numero de iteraciones invalido!");
     if iteraciones < 1 {
         eprintln!("Verificar el ejemplo y el numero de itereaciones > 0");
+        std::process::exit(1);
     }
     let filepath: String = args[5].parse().expect("String invalido!");
 
-    println!("Total_hormigas: {} hormigas.", n_hormigas);
-    println!("Matriz: {}", filepath);
-
-    let _ = sistema_hormigas(
+    let mut configuracion = Configuracion {
         n_hormigas,
-        importancia_distancia,
         importancia_feromona,
-        iteraciones,
-        filepath,
+        importancia_distancia,
+        max_iteraciones: iteraciones,
+        ..Configuracion::default()
+    };
+    // <nodo_inicio> <nodo_destino> opcionales en modo laberinto
+    let mut extremos = None;
+    let mut opciones = &args[6..];
+    if modo_laberinto && args.len() >= 8 && !args[6].starts_with("--") {
+        match (args[6].parse::<Nodo>(), args[7].parse::<Nodo>()) {
+            (Ok(origen), Ok(destino)) => extremos = Some((origen, destino)),
+            _ => {
+                eprintln!("Nodos de inicio y destino invalidos");
+                std::process::exit(1);
+            }
+        }
+        opciones = &args[8..];
+    }
+    let mut segunda_matriz = None;
+    if modo_biobjetivo {
+        match args.get(6).filter(|a| !a.starts_with("--")) {
+            Some(segunda) => segunda_matriz = Some(segunda.clone()),
+            None => {
+                eprintln!("Falta la segunda matriz del modo biobjetivo");
+                std::process::exit(1);
+            }
+        }
+        opciones = &args[7..];
+    }
+    if let Err(e) = leer_opciones(opciones, &mut configuracion) {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
+    if let Some(semilla) = configuracion.semilla {
+        fijar_semilla(semilla);
+    }
+
+    println!("Total_hormigas: {} hormigas.", n_hormigas);
+    println!(
+        "Importancia de la feromona (alfa): {}",
+        importancia_feromona
+    );
+    println!(
+        "Importancia de la distancia (beta): {}",
+        importancia_distancia
     );
+    if modo_laberinto {
+        println!("Rho (factor de evaporacion): {}", configuracion.rho);
+        println!("Laberinto: {}", filepath);
+        if let Err(e) = camino_laberinto(&configuracion, &filepath, extremos) {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+    if modo_ventanas {
+        println!("Rho (factor de evaporacion): {}", configuracion.rho);
+        println!("Ventanas de tiempo: {}", filepath);
+        if let Err(e) = camino_ventanas(&configuracion, &filepath) {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+    if let Some(segunda) = segunda_matriz {
+        println!("Rho (factor de evaporacion): {}", configuracion.rho);
+        println!("Listas de candidatos: {}", configuracion.n_candidatos);
+        println!("Matrices: {} y {}", filepath, segunda);
+        if let Err(e) = frente_biobjetivo(&configuracion, &filepath, &segunda) {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+    println!("Modo: {:?}", configuracion.modo);
+    println!("Rho (factor de evaporacion): {}", configuracion.rho);
+    if configuracion.modo == Modo::SistemaHormigas {
+        println!("Actualizacion: {:?}", configuracion.estrategia);
+    }
+    if configuracion.modo == Modo::SistemaColonia {
+        println!("q0: {}, xi: {}", configuracion.q0, configuracion.xi);
+    }
+    if configuracion.modo == Modo::MaxMin {
+        println!(
+            "p_mejor: {}, frecuencia mejor global: {}, reinicio: {} iteraciones",
+            configuracion.p_mejor,
+            configuracion.frecuencia_mejor_global,
+            configuracion.iteraciones_reinicio
+        );
+    }
+    if configuracion.estancamiento.accion != AccionEstancamiento::Ninguna {
+        println!("Estancamiento: {:?}", configuracion.estancamiento);
+    }
+    println!("Busqueda local: {:?}", configuracion.busqueda_local);
+    println!("Listas de candidatos: {}", configuracion.n_candidatos);
+    println!("Matriz: {}", filepath);
+
+    if let Err(e) = sistema_hormigas(&configuracion, filepath) {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Con semilla fija, Ant System llega al optimo de br17 (39, el mismo
+    // que da Held-Karp) dentro de 100 iteraciones
+    #[test]
+    fn colonia_llega_al_optimo_de_br17() {
+        let configuracion = Configuracion {
+            n_hormigas: 10,
+            importancia_feromona: 1.0,
+            importancia_distancia: 2.0,
+            max_iteraciones: 100,
+            ..Configuracion::default()
+        };
+        fijar_semilla(1);
+        let (camino, costo) = sistema_hormigas(&configuracion, "src/files/br17.txt".into())
+            .unwrap()
+            .unwrap();
+        let file = leer_matriz("src/files/br17.txt").unwrap();
+        assert_eq!(costo, 39);
+        assert_eq!(costo_camino_cerrado(&camino, &file.matriz), 39);
+        assert_eq!(held_karp(&file).unwrap().1, costo);
+    }
 }
@@ -0,0 +1,429 @@
+use crate::sistema_hormigas::{Camino, Distancias, Nodo};
+use std::time::{Duration, Instant};
+
+// Ramificacion y acotamiento para el ATSP. La cota inferior de cada nodo es
+// el problema de asignacion (relajacion sin restricciones de subciclos),
+// resuelto con el metodo hungaro en O(n^3). Si la asignacion forma varios
+// subciclos se ramifica sobre las aristas del subciclo mas corto (esquema de
+// Carpaneto y Toth): el hijo r excluye la arista r e incluye las r - 1
+// anteriores, de modo que los hijos particionan el espacio de soluciones.
+
+// Resultado de la busqueda, termine o no dentro del tiempo limite
+#[derive(Debug, Clone)]
+pub struct ResultadoRamificacion {
+    // Mejor camino encontrado y su costo (la cota superior)
+    pub camino: Option<Camino>,
+    pub costo: Option<u32>,
+    // Cota inferior certificada del optimo
+    pub cota_inferior: u32,
+    // true si se exploro todo el arbol: el costo es optimo
+    pub optimo: bool,
+    pub nodos_explorados: usize,
+}
+
+// Restricciones acumuladas de un nodo del arbol
+#[derive(Debug, Clone)]
+struct NodoArbol {
+    excluidas: Vec<(usize, usize)>,
+    incluidas: Vec<(usize, usize)>,
+    cota: i64,
+    asignacion: Asignacion,
+}
+
+// Asignacion de costo minimo en una matriz n x n por filas, con los
+// potenciales duales del metodo hungaro. Un hijo solo encarece aristas de su
+// padre, asi que los potenciales del padre siguen siendo factibles y basta
+// liberar la arista excluida y reasignar su fila: O(n^2) en lugar de O(n^3).
+#[derive(Debug, Clone)]
+struct Asignacion {
+    // Indices desde 1; la columna 0 es ficticia
+    u: Vec<i64>,
+    v: Vec<i64>,
+    fila_de: Vec<usize>,
+}
+
+impl Asignacion {
+    fn vacia(n: usize) -> Asignacion {
+        Asignacion {
+            u: vec![0; n + 1],
+            v: vec![0; n + 1],
+            fila_de: vec![0; n + 1],
+        }
+    }
+
+    // Quita la arista (i, j) (indices desde 0) de la asignacion
+    fn liberar(&mut self, (i, j): (usize, usize)) {
+        debug_assert_eq!(self.fila_de[j + 1], i + 1);
+        self.fila_de[j + 1] = 0;
+    }
+
+    // Filas sin columna asignada (indices desde 1)
+    fn filas_libres(&self) -> Vec<usize> {
+        let n = self.fila_de.len() - 1;
+        let mut asignada = vec![false; n + 1];
+        for &fila in &self.fila_de[1..] {
+            asignada[fila] = true;
+        }
+        (1..=n).filter(|&i| !asignada[i]).collect()
+    }
+
+    // Asigna la fila i por el camino aumentante de menor costo reducido
+    fn aumentar(&mut self, costos: &[i64], i: usize) {
+        let n = self.fila_de.len() - 1;
+        let Asignacion { u, v, fila_de } = self;
+        let mut camino = vec![0usize; n + 1];
+        fila_de[0] = i;
+        let mut j0 = 0;
+        let mut minimo = vec![i64::MAX; n + 1];
+        let mut usado = vec![false; n + 1];
+        loop {
+            usado[j0] = true;
+            let i0 = fila_de[j0];
+            let mut delta = i64::MAX;
+            let mut j1 = 0;
+            for j in 1..=n {
+                if usado[j] {
+                    continue;
+                }
+                let reducido = costos[(i0 - 1) * n + (j - 1)] - u[i0] - v[j];
+                if reducido < minimo[j] {
+                    minimo[j] = reducido;
+                    camino[j] = j0;
+                }
+                if minimo[j] < delta {
+                    delta = minimo[j];
+                    j1 = j;
+                }
+            }
+            for j in 0..=n {
+                if usado[j] {
+                    u[fila_de[j]] += delta;
+                    v[j] -= delta;
+                } else {
+                    minimo[j] -= delta;
+                }
+            }
+            j0 = j1;
+            if fila_de[j0] == 0 {
+                break;
+            }
+        }
+        // Aumentar a lo largo del camino alternante
+        loop {
+            let j1 = camino[j0];
+            fila_de[j0] = fila_de[j1];
+            j0 = j1;
+            if j0 == 0 {
+                break;
+            }
+        }
+    }
+
+    fn costo(&self, costos: &[i64]) -> i64 {
+        let n = self.fila_de.len() - 1;
+        (1..=n)
+            .map(|j| costos[(self.fila_de[j] - 1) * n + (j - 1)])
+            .sum()
+    }
+
+    // Sucesor de cada fila (indices desde 0)
+    fn sucesores(&self) -> Vec<usize> {
+        let n = self.fila_de.len() - 1;
+        let mut sucesores = vec![0; n];
+        for j in 1..=n {
+            sucesores[self.fila_de[j] - 1] = j - 1;
+        }
+        sucesores
+    }
+}
+
+// Costo de una arista prohibida (diagonal, excluidas o bloqueadas por una
+// inclusion): n * d_max + 1 supera el costo de cualquier asignacion sin
+// aristas prohibidas, asi que una asignacion que llega a el es infactible.
+// Derivarlo de la instancia mantiene los potenciales del metodo hungaro
+// (del orden de n veces este costo) lejos del desbordamiento de i64.
+fn costo_prohibido(distancias: &Distancias) -> i64 {
+    let n = distancias.len();
+    let maximo = distancias
+        .iter()
+        .enumerate()
+        .flat_map(|(i, fila)| {
+            fila.iter()
+                .enumerate()
+                .filter(move |&(j, _)| j != i)
+                .map(|(_, &d)| d as i64)
+        })
+        .max()
+        .unwrap_or(0);
+    n as i64 * maximo + 1
+}
+
+// Matriz de costos de un nodo con sus exclusiones e inclusiones aplicadas
+fn costos_nodo(distancias: &Distancias, nodo: &NodoArbol, prohibido: i64) -> Vec<i64> {
+    let n = distancias.len();
+    let mut costos: Vec<i64> = distancias
+        .iter()
+        .flat_map(|fila| fila.iter().map(|&d| d as i64))
+        .collect();
+    for i in 0..n {
+        costos[i * n + i] = prohibido;
+    }
+    for &(i, j) in &nodo.excluidas {
+        costos[i * n + j] = prohibido;
+    }
+    // Incluir (i, j): i no puede ir a otro nodo ni otro nodo puede llegar a j
+    for &(i, j) in &nodo.incluidas {
+        for k in 0..n {
+            if k != j {
+                costos[i * n + k] = prohibido;
+            }
+            if k != i {
+                costos[k * n + j] = prohibido;
+            }
+        }
+    }
+    costos
+}
+
+// Completa la asignacion del nodo (la del padre sin la arista excluida, o
+// vacia en la raiz); None si es infactible
+fn acotar(
+    distancias: &Distancias,
+    prohibido: i64,
+    excluidas: Vec<(usize, usize)>,
+    incluidas: Vec<(usize, usize)>,
+    mut asignacion: Asignacion,
+) -> Option<NodoArbol> {
+    let mut nodo = NodoArbol {
+        excluidas,
+        incluidas,
+        cota: 0,
+        asignacion: Asignacion::vacia(0),
+    };
+    let costos = costos_nodo(distancias, &nodo, prohibido);
+    for fila in asignacion.filas_libres() {
+        asignacion.aumentar(&costos, fila);
+    }
+    let cota = asignacion.costo(&costos);
+    if cota >= prohibido {
+        return None;
+    }
+    nodo.cota = cota;
+    nodo.asignacion = asignacion;
+    Some(nodo)
+}
+
+// Subciclos de la asignacion, cada uno como lista de nodos en orden
+fn subciclos(sucesores: &[usize]) -> Vec<Vec<usize>> {
+    let n = sucesores.len();
+    let mut visitado = vec![false; n];
+    let mut ciclos = Vec::new();
+    for inicio in 0..n {
+        if visitado[inicio] {
+            continue;
+        }
+        let mut ciclo = Vec::new();
+        let mut actual = inicio;
+        while !visitado[actual] {
+            visitado[actual] = true;
+            ciclo.push(actual);
+            actual = sucesores[actual];
+        }
+        ciclos.push(ciclo);
+    }
+    ciclos
+}
+
+fn costo_camino(camino: &Camino, distancias: &Distancias) -> u32 {
+    let n = camino.len();
+    (0..n)
+        .map(|i| distancias[camino[i] as usize][camino[(i + 1) % n] as usize])
+        .sum()
+}
+
+// Busqueda en profundidad (primero el hijo con menor cota) hasta agotar el
+// arbol o el tiempo limite. `camino_inicial` (p. ej. el mejor de la colonia)
+// es la cota superior de partida.
+pub fn ramificacion_y_acotamiento(
+    distancias: &Distancias,
+    camino_inicial: Option<&Camino>,
+    tiempo_limite: Duration,
+) -> ResultadoRamificacion {
+    let inicio = Instant::now();
+    let n = distancias.len();
+
+    let mut mejor_camino: Option<Camino> =
+        camino_inicial.filter(|camino| camino.len() == n).cloned();
+    let mut mejor_costo: i64 = mejor_camino
+        .as_ref()
+        .map_or(i64::MAX, |camino| costo_camino(camino, distancias) as i64);
+
+    let prohibido = costo_prohibido(distancias);
+    let Some(raiz) = acotar(
+        distancias,
+        prohibido,
+        Vec::new(),
+        Vec::new(),
+        Asignacion::vacia(n),
+    ) else {
+        // Ninguna asignacion factible: tampoco hay camino
+        return ResultadoRamificacion {
+            camino: None,
+            costo: None,
+            cota_inferior: 0,
+            optimo: true,
+            nodos_explorados: 1,
+        };
+    };
+
+    let mut pila = vec![raiz];
+    let mut nodos_explorados = 0;
+    let mut completo = true;
+
+    while let Some(nodo) = pila.pop() {
+        if nodo.cota >= mejor_costo {
+            continue;
+        }
+        if inicio.elapsed() >= tiempo_limite {
+            pila.push(nodo);
+            completo = false;
+            break;
+        }
+        nodos_explorados += 1;
+
+        let sucesores = nodo.asignacion.sucesores();
+        let ciclos = subciclos(&sucesores);
+        if ciclos.len() == 1 {
+            // La asignacion es un camino: nueva cota superior
+            mejor_costo = nodo.cota;
+            mejor_camino = Some(ciclos[0].iter().map(|&i| i as Nodo).collect());
+            continue;
+        }
+
+        // Ramificar sobre las aristas libres del subciclo mas corto
+        let ciclo = ciclos.iter().min_by_key(|ciclo| ciclo.len()).unwrap();
+        let aristas_libres: Vec<(usize, usize)> = ciclo
+            .iter()
+            .map(|&i| (i, sucesores[i]))
+            .filter(|arista| !nodo.incluidas.contains(arista))
+            .collect();
+
+        let mut hijos = Vec::with_capacity(aristas_libres.len());
+        for (r, &arista) in aristas_libres.iter().enumerate() {
+            let mut excluidas = nodo.excluidas.clone();
+            excluidas.push(arista);
+            let mut incluidas = nodo.incluidas.clone();
+            incluidas.extend_from_slice(&aristas_libres[..r]);
+            let mut asignacion = nodo.asignacion.clone();
+            asignacion.liberar(arista);
+            if let Some(hijo) = acotar(distancias, prohibido, excluidas, incluidas, asignacion) {
+                if hijo.cota < mejor_costo {
+                    hijos.push(hijo);
+                }
+            }
+        }
+
+        // El hijo con menor cota queda al final de la pila
+        hijos.sort_by_key(|hijo| std::cmp::Reverse(hijo.cota));
+        pila.extend(hijos);
+    }
+
+    // Sin terminar, la cota inferior es la menor cota pendiente
+    let cota_inferior = if completo {
+        mejor_costo
+    } else {
+        pila.iter()
+            .map(|nodo| nodo.cota)
+            .min()
+            .unwrap_or(mejor_costo)
+            .min(mejor_costo)
+    };
+
+    ResultadoRamificacion {
+        costo: mejor_camino.as_ref().map(|_| mejor_costo as u32),
+        camino: mejor_camino,
+        cota_inferior: cota_inferior.clamp(0, u32::MAX as i64) as u32,
+        optimo: completo,
+        nodos_explorados,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::held_karp::held_karp;
+    use crate::sistema_hormigas::{costo_camino_cerrado, leer_matriz, MatrizFromFile};
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    fn resolver(file: &MatrizFromFile) -> ResultadoRamificacion {
+        ramificacion_y_acotamiento(&file.matriz, None, Duration::from_secs(60))
+    }
+
+    #[test]
+    fn coincide_con_held_karp_en_br17() {
+        let file = leer_matriz("src/files/br17.txt").unwrap();
+        let (_, optimo) = held_karp(&file).unwrap();
+        let resultado = resolver(&file);
+        assert!(resultado.optimo);
+        assert_eq!(resultado.costo, Some(optimo));
+        assert_eq!(resultado.cota_inferior, optimo);
+        let camino = resultado.camino.unwrap();
+        assert_eq!(costo_camino_cerrado(&camino, &file.matriz), optimo as u64);
+    }
+
+    #[test]
+    fn coincide_con_held_karp_en_matrices_aleatorias() {
+        let mut generador = StdRng::seed_from_u64(7);
+        for _ in 0..20 {
+            let n = generador.random_range(2..=9);
+            // Pesos grandes, aunque el costo de un camino cabe en u32
+            let matriz: Distancias = (0..n)
+                .map(|i| {
+                    (0..n)
+                        .map(|j| {
+                            if i == j {
+                                0
+                            } else {
+                                generador.random_range(0..u32::MAX / 16)
+                            }
+                        })
+                        .collect()
+                })
+                .collect();
+            let file = MatrizFromFile::new(
+                "aleatoria".into(),
+                "ATSP".into(),
+                matriz,
+                n as u32,
+                None,
+                None,
+                Vec::new(),
+            );
+            let (_, optimo) = held_karp(&file).unwrap();
+            let resultado = resolver(&file);
+            assert!(resultado.optimo);
+            assert_eq!(resultado.costo, Some(optimo));
+        }
+    }
+
+    #[test]
+    fn asignacion_solo_con_aristas_prohibidas_es_infactible() {
+        // Todas las aristas excluidas: la asignacion solo puede usar aristas
+        // prohibidas, con los pesos mas grandes que admite un camino u32
+        let n = 12;
+        let distancias: Distancias = vec![vec![u32::MAX / 16; n]; n];
+        let excluidas = (0..n)
+            .flat_map(|i| (0..n).filter(move |&j| j != i).map(move |j| (i, j)))
+            .collect();
+        let prohibido = costo_prohibido(&distancias);
+        assert!(acotar(
+            &distancias,
+            prohibido,
+            excluidas,
+            Vec::new(),
+            Asignacion::vacia(n)
+        )
+        .is_none());
+    }
+}
@@ -1,28 +1,168 @@
+pub use crate::busqueda_local::*;
+pub use crate::candidatos::*;
+pub use crate::estancamiento::*;
+pub use crate::feromonas::*;
+pub use crate::tsplib::*;
+pub use colonia::LimitesFeromona;
 use rand::prelude::*;
-use std::collections::HashMap;
+use std::cell::RefCell;
 use std::error::Error;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
 
 pub type Nodo = u32;
 pub type Peso = f64;
 pub type Distancia = Vec<u32>;
 pub type Base = Vec<Vec<Nodo>>;
-pub type Feromonas = Vec<HashMap<Nodo, Peso>>;
+pub type Feromonas = MatrizAristas;
+pub type Heuristica = MatrizAristas;
+pub type ValoresEleccion = MatrizAristas;
 pub type Distancias = Vec<Distancia>;
 pub type Camino = Vec<Nodo>;
 pub type Hormigas = Vec<Hormiga>;
 pub type EvaluacionCaminos = Distancia;
 pub type Rho = f64;
+pub type ImportanciaDistancia = f64;
+pub type ImportanciaFeromona = f64;
+// Para cada nodo, los nodos que deben visitarse antes (SOP)
+pub type Precedencias = Vec<Vec<Nodo>>;
 
 pub struct MatrizFromFile {
+    pub nombre: String,
+    pub tipo: String,
     pub matriz: Distancias,
     pub dimension: u32,
+    // BEST_KNOWN del archivo o de la tabla de optimos de TSPLIB
+    pub mejor_conocido: Option<u32>,
+    // Aristas de EDGE_DATA_SECTION; None si el grafo es completo
+    pub aristas: Option<Base>,
+    // Restricciones de precedencia de las instancias SOP (vacias en otro caso)
+    pub precedencias: Precedencias,
 }
 
 impl MatrizFromFile {
-    fn new(matriz: Distancias, dimension: u32) -> MatrizFromFile {
-        MatrizFromFile { matriz, dimension }
+    pub fn new(
+        nombre: String,
+        tipo: String,
+        matriz: Distancias,
+        dimension: u32,
+        mejor_conocido: Option<u32>,
+        aristas: Option<Base>,
+        precedencias: Precedencias,
+    ) -> MatrizFromFile {
+        MatrizFromFile {
+            nombre,
+            tipo,
+            matriz,
+            dimension,
+            mejor_conocido,
+            aristas,
+            precedencias,
+        }
+    }
+
+    // Sequential Ordering Problem: camino del nodo 0 al nodo n - 1 que
+    // respeta las precedencias
+    pub fn es_sop(&self) -> bool {
+        self.precedencias.iter().any(|previos| !previos.is_empty())
+    }
+}
+
+// Variante del algoritmo que se ejecuta en el ciclo principal
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Modo {
+    // Ant System: todas las hormigas depositan y se evapora todo el rastro
+    SistemaHormigas,
+    // Ant Colony System: regla pseudoaleatoria proporcional (q0),
+    // actualizacion local al construir y global con el mejor camino
+    SistemaColonia,
+    // MAX-MIN Ant System: solo deposita la mejor hormiga, feromona acotada
+    // en [τmin, τmax] y reinicio de rastros al estancarse
+    MaxMin,
+}
+
+// Que hormigas depositan feromona en Ant System y con que peso
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EstrategiaActualizacion {
+    // Todas las hormigas depositan 1 / L_k con el mismo peso
+    TodasLasHormigas,
+    // Todas depositan y ademas la mejor global deposita e / L_mejor
+    Elitista,
+    // Las w - 1 mejores de la iteracion depositan (w - r) / L_r y la mejor
+    // global deposita w / L_mejor
+    Rango,
+    // Solo deposita la mejor hormiga de la iteracion
+    MejorIteracion,
+}
+
+pub struct Configuracion {
+    pub n_hormigas: usize,
+    pub importancia_feromona: ImportanciaFeromona,
+    pub importancia_distancia: ImportanciaDistancia,
+    pub max_iteraciones: usize,
+    pub modo: Modo,
+    // Regla de deposito en Ant System
+    pub estrategia: EstrategiaActualizacion,
+    // Peso e de la mejor global en la estrategia elitista
+    pub peso_elitista: f64,
+    // Numero w de hormigas que depositan en la estrategia por rango
+    pub hormigas_rango: usize,
+    // Factor de evaporacion (en ACS es el peso de la actualizacion global)
+    pub rho: Rho,
+    // Probabilidad de explotar el mejor vertice en ACS
+    pub q0: f64,
+    // Factor de la actualizacion local en ACS
+    pub xi: Rho,
+    // Probabilidad de construir el mejor camino al converger (MMAS)
+    pub p_mejor: f64,
+    // Cada cuantas iteraciones deposita la mejor global en vez de la mejor
+    // de la iteracion (MMAS)
+    pub frecuencia_mejor_global: usize,
+    // Iteraciones sin mejora antes de reiniciar los rastros (MMAS)
+    pub iteraciones_reinicio: usize,
+    // Hormigas a las que se aplica Or-opt y 3-opt antes de evaluar
+    pub busqueda_local: BusquedaLocal,
+    // Tamaño k de las listas de candidatos (0 = vecindad completa)
+    pub n_candidatos: usize,
+    // Segundos de ramificacion y acotamiento al terminar (0 = no se ejecuta)
+    pub tiempo_ramificacion: f64,
+    // Pesos que indican una arista inexistente
+    pub centinelas: Vec<u32>,
+    // Archivo .tour donde se guarda el mejor camino
+    pub archivo_tour: Option<String>,
+    // Punto de referencia del hipervolumen en modo biobjetivo (None = 10 %
+    // por encima del peor valor de cada objetivo en el frente)
+    pub referencia_hipervolumen: Option<(f64, f64)>,
+    // Cuando se considera estancada la colonia y que hacer entonces
+    pub estancamiento: CriterioEstancamiento,
+    // Semilla del generador aleatorio (None = semilla del sistema)
+    pub semilla: Option<u64>,
+}
+
+impl Default for Configuracion {
+    fn default() -> Configuracion {
+        Configuracion {
+            n_hormigas: 10,
+            importancia_feromona: 1.0,
+            importancia_distancia: 2.0,
+            max_iteraciones: 100,
+            modo: Modo::SistemaHormigas,
+            estrategia: EstrategiaActualizacion::TodasLasHormigas,
+            peso_elitista: 1.0,
+            hormigas_rango: 6,
+            rho: 0.3,
+            q0: 0.9,
+            xi: 0.1,
+            p_mejor: 0.05,
+            frecuencia_mejor_global: 5,
+            iteraciones_reinicio: 100,
+            busqueda_local: BusquedaLocal::Ninguna,
+            n_candidatos: 20,
+            tiempo_ramificacion: 0.0,
+            centinelas: Vec::new(),
+            archivo_tour: None,
+            referencia_hipervolumen: None,
+            estancamiento: CriterioEstancamiento::default(),
+            semilla: None,
+        }
     }
 }
 
@@ -39,26 +179,94 @@ impl Hormiga {
     }
 }
 
+// Generador de todas las decisiones aleatorias de la colonia. Es local al
+// hilo para que una semilla fija reproduzca la ejecucion completa.
+thread_local! {
+    static GENERADOR: RefCell<StdRng> = RefCell::new(StdRng::from_os_rng());
+}
+
+pub fn fijar_semilla(semilla: u64) {
+    GENERADOR.with(|generador| *generador.borrow_mut() = StdRng::seed_from_u64(semilla));
+}
+
+fn con_generador<T>(f: impl FnOnce(&mut StdRng) -> T) -> T {
+    GENERADOR.with(|generador| f(&mut generador.borrow_mut()))
+}
+
+// Porcentaje por encima del mejor valor conocido
+pub fn gap_porcentual(costo: u32, mejor_conocido: u32) -> f64 {
+    (costo as f64 - mejor_conocido as f64) / mejor_conocido.max(1) as f64 * 100.0
+}
+
 pub fn get_inicio(dimension: &u32) -> u32 {
-    rand::random_range(0..*dimension)
+    con_generador(|rng| rng.random_range(0..*dimension))
 }
 
 pub fn algoritmo_inicializacion(conjunto_aristas: &Base) -> Feromonas {
     // Agregar valores aletatorios de feromonas en las aristas
-    let mut feromonas: Feromonas = vec![HashMap::new(); conjunto_aristas.len()];
-    for (index, conexiones_vecinos) in conjunto_aristas.iter().enumerate() {
-        for conexion in conexiones_vecinos.iter() {
+    con_generador(|rng| {
+        MatrizAristas::new(conjunto_aristas, |_, _| {
             // Generacion valor aleatorio y redondeo de decimales
-            let mut rng = rand::rng();
-            let mut feromona: Peso = rng.random_range(0.01..=0.1);
+            let feromona: Peso = rng.random_range(0.01..=0.1);
+            (feromona * 1_000.0).round() / 1_000.0
+        })
+    })
+}
 
-            feromona = (feromona * 1_000.0).round() / 1_000.0;
-            // Asignacion de feromona a arista
-            feromonas[index].insert(*conexion, feromona);
-        }
+pub fn inicializacion_uniforme(conjunto_aristas: &Base, feromona: Peso) -> Feromonas {
+    // Mismo valor de feromona en todas las aristas (τ0 en ACS)
+    MatrizAristas::new(conjunto_aristas, |_, _| feromona)
+}
+
+pub fn costo_vecino_mas_cercano(
+    conjunto_aristas: &Base,
+    distancias: &Distancias,
+    inicio: Nodo,
+) -> u32 {
+    // Camino goloso: siempre al vecino no visitado mas cercano. La suma va en
+    // u64 para que los pesos grandes no la desborden
+    let n = conjunto_aristas.len();
+    let mut visitados = vec![false; n];
+    let mut actual = inicio;
+    let mut costo: u64 = 0;
+    let mut longitud = 1;
+    visitados[inicio as usize] = true;
+
+    while let Some(&siguiente) = conjunto_aristas[actual as usize]
+        .iter()
+        .filter(|&&j| !visitados[j as usize])
+        .min_by_key(|&&j| distancias[actual as usize][j as usize])
+    {
+        costo += distancias[actual as usize][siguiente as usize] as u64;
+        visitados[siguiente as usize] = true;
+        actual = siguiente;
+        longitud += 1;
     }
 
-    feromonas
+    let regreso = distancias[actual as usize][inicio as usize];
+    let cierra = n == 1 || conjunto_aristas[actual as usize].contains(&inicio);
+    if longitud == n && cierra && regreso < DISTANCIA_PROHIBIDA {
+        return u32::try_from(costo + regreso as u64).unwrap_or(u32::MAX);
+    }
+    // El goloso se quedo sin salida: n veces el peso promedio de las aristas
+    costo_promedio_aristas(conjunto_aristas, distancias)
+}
+
+// n veces el peso promedio de las aristas existentes, como estimacion del
+// costo de un camino cuando el vecino mas cercano no completa uno
+fn costo_promedio_aristas(conjunto_aristas: &Base, distancias: &Distancias) -> u32 {
+    let pesos: Vec<u64> = conjunto_aristas
+        .iter()
+        .enumerate()
+        .flat_map(|(i, vecinos)| vecinos.iter().map(move |&j| distancias[i][j as usize]))
+        .filter(|&peso| peso < DISTANCIA_PROHIBIDA)
+        .map(|peso| peso as u64)
+        .collect();
+    if pesos.is_empty() {
+        return 1;
+    }
+    let promedio = pesos.iter().sum::<u64>() / pesos.len() as u64;
+    u32::try_from(promedio * conjunto_aristas.len() as u64).unwrap_or(u32::MAX)
 }
 
 pub fn inicializacion_hormigas(cantidad_hormigas: usize, inicio: u32) -> Hormigas {
@@ -72,91 +280,180 @@ pub fn inicializacion_hormigas(cantidad_hormigas: usize, inicio: u32) -> Hormiga
 
 pub fn seleccion_ruleta(
     origen: Nodo,
-    vertices_factibles: &mut Camino,
-    feromonas: &Feromonas,
-    distancias: &Distancias,
+    vertices_factibles: &[Nodo],
+    valores_eleccion: &ValoresEleccion,
+    pesos: &mut Vec<Peso>,
 ) -> Option<Nodo> {
-    let mut rng = rand::rng();
-    // Generar umbral aleatorio
-    let umbral: f64 = rng.random_range(0.0..=1.0);
-    let mut proporcion = 0.0;
-    // Suma total de feromonas en los vertices factibles
-    let total_feromonas: Peso = vertices_factibles
-        .iter()
-        .map(|nodo| feromonas[origen as usize].get(&nodo).copied().unwrap())
-        .sum();
+    // Peso de cada vertice factible: τ_ij^α * η_ij^β, precalculado en la
+    // iteracion. Una distancia 0 da visibilidad infinita y la ruleta elige
+    // uniformemente entre esos vertices. `pesos` es memoria de trabajo de la
+    // hormiga, reusada en cada paso.
+    pesos.clear();
+    pesos.extend(
+        vertices_factibles
+            .iter()
+            .map(|&j| valores_eleccion.get(origen, j)),
+    );
+
+    con_generador(|rng| ruleta::seleccionar(pesos, rng)).map(|indice| vertices_factibles[indice])
+}
 
-    if total_feromonas == 0.0 {
-        return None;
+pub fn seleccion_pseudoaleatoria(
+    origen: Nodo,
+    vertices_factibles: &[Nodo],
+    valores_eleccion: &ValoresEleccion,
+    q0: f64,
+    pesos: &mut Vec<Peso>,
+) -> Option<Nodo> {
+    // Con probabilidad q0 se explota el vertice con mayor τ_ij^α * η_ij^β,
+    // en otro caso se explora con la ruleta
+    if con_generador(|rng| rng.random::<f64>()) >= q0 {
+        return seleccion_ruleta(origen, vertices_factibles, valores_eleccion, pesos);
     }
 
-    let total_distancias: u32 = vertices_factibles
-        .iter()
-        .map(|n| distancias[origen as usize][*n as usize])
-        .sum::<u32>();
-
-    let total = total_feromonas / total_distancias as f64;
-
-    loop {
-        if vertices_factibles.len() == 1 {
-            return vertices_factibles.last().copied();
-        }
-
-        // Elejir vertice aleatoriamente
-        let indice_nodo = rng.random_range(0..=vertices_factibles.len() - 1);
-        // Remover j de las posibles selecciones
-        let j: Nodo = vertices_factibles.remove(indice_nodo);
-
-        let feromona_ij = feromonas[origen as usize].get(&j).copied().unwrap();
-
-        let distancia_ij = distancias[origen as usize][j as usize] as Peso;
+    vertices_factibles.iter().copied().max_by(|&a, &b| {
+        valores_eleccion
+            .get(origen, a)
+            .total_cmp(&valores_eleccion.get(origen, b))
+    })
+}
 
-        if distancia_ij == 0.0 {
-            return Some(j);
+pub fn construccion_caminos(
+    conjunto_aristas: &Base,
+    candidatos: &ListasCandidatos,
+    precedencias: &Precedencias,
+    valores_eleccion: &ValoresEleccion,
+    hormigas: &mut Hormigas,
+) {
+    let total_nodos = conjunto_aristas.len();
+    for hormiga in hormigas.iter_mut() {
+        let mut visitados = Visitados::new(total_nodos);
+        let mut pesos = Vec::with_capacity(total_nodos);
+        for &nodo in &hormiga.camino {
+            visitados.insertar(nodo);
         }
 
-        proporcion += feromona_ij / (distancia_ij * total);
-        // println!(
-        //     "Proporcion: {}, Distancia: {}, Feromona: {}",
-        //     &proporcion, distancia_ij, feromona_ij
-        // );
+        while hormiga.camino.len() < total_nodos {
+            let origen = *hormiga.camino.last().unwrap();
 
-        if proporcion >= umbral {
-            return Some(j);
+            // Candidatos no visitados, o todos los vecinos no visitados
+            let vertices_factibles = vertices_factibles(
+                origen,
+                conjunto_aristas,
+                candidatos,
+                precedencias,
+                &visitados,
+            );
+
+            if let Some(siguiente) =
+                seleccion_ruleta(origen, &vertices_factibles, valores_eleccion, &mut pesos)
+            {
+                hormiga.camino.push(siguiente);
+                visitados.insertar(siguiente);
+            } else {
+                // Callejon sin salida: el camino queda incompleto y la
+                // hormiga se descarta al evaluar
+                break;
+            }
         }
     }
 }
 
-pub fn construccion_caminos(
+#[allow(clippy::too_many_arguments)]
+pub fn construccion_caminos_acs(
     conjunto_aristas: &Base,
-    feromonas: &Feromonas,
+    candidatos: &ListasCandidatos,
+    precedencias: &Precedencias,
+    feromonas: &mut Feromonas,
+    valores_eleccion: &mut ValoresEleccion,
+    heuristica: &Heuristica,
     hormigas: &mut Hormigas,
-    distancias: &Distancias,
+    i_f: ImportanciaFeromona,
+    q0: f64,
+    xi: Rho,
+    tau0: Peso,
 ) {
     let total_nodos = conjunto_aristas.len();
     for hormiga in hormigas.iter_mut() {
+        let mut visitados = Visitados::new(total_nodos);
+        let mut pesos = Vec::with_capacity(total_nodos);
+        for &nodo in &hormiga.camino {
+            visitados.insertar(nodo);
+        }
+
         while hormiga.camino.len() < total_nodos {
             let origen = *hormiga.camino.last().unwrap();
+            let vertices_factibles = vertices_factibles(
+                origen,
+                conjunto_aristas,
+                candidatos,
+                precedencias,
+                &visitados,
+            );
 
-            // Generar los nodos aun no visitados
-            let visitados = &hormiga.camino;
-            let vecinos: &Camino = &conjunto_aristas[origen as usize];
-            let vertices_factibles: Camino = vecinos
-                .iter()
-                .copied()
-                .filter(|n| !visitados.contains(n))
-                .collect();
+            let Some(siguiente) = seleccion_pseudoaleatoria(
+                origen,
+                &vertices_factibles,
+                valores_eleccion,
+                q0,
+                &mut pesos,
+            ) else {
+                // Callejon sin salida: el camino queda incompleto
+                break;
+            };
 
-            if let Some(siguiente) = seleccion_ruleta(
+            actualizacion_local_acs(
                 origen,
-                &mut vertices_factibles.clone(),
-                &feromonas,
-                &distancias,
-            ) {
-                hormiga.camino.push(siguiente);
-            } else {
-                break; // No hay opciones, termina la construccion
-            }
+                siguiente,
+                feromonas,
+                valores_eleccion,
+                heuristica,
+                i_f,
+                xi,
+                tau0,
+            );
+            hormiga.camino.push(siguiente);
+            visitados.insertar(siguiente);
+        }
+
+        // Arista de regreso al inicio, solo si el camino es una solucion
+        if !camino_completo(conjunto_aristas, &hormiga.camino) {
+            continue;
+        }
+        let (Some(&inicio), Some(&ultimo)) = (hormiga.camino.first(), hormiga.camino.last()) else {
+            continue;
+        };
+        actualizacion_local_acs(
+            ultimo,
+            inicio,
+            feromonas,
+            valores_eleccion,
+            heuristica,
+            i_f,
+            xi,
+            tau0,
+        );
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn actualizacion_local_acs(
+    origen: Nodo,
+    destino: Nodo,
+    feromonas: &mut Feromonas,
+    valores_eleccion: &mut ValoresEleccion,
+    heuristica: &Heuristica,
+    i_f: ImportanciaFeromona,
+    xi: Rho,
+    tau0: Peso,
+) {
+    // Actualizacion local: τ_ij = (1 - ξ) * τ_ij + ξ * τ0, y el valor de
+    // eleccion de la arista se mantiene al dia para las siguientes hormigas
+    if let Some(peso) = feromonas.get_mut(origen, destino) {
+        *peso = (1.0 - xi) * *peso + xi * tau0;
+        let valor = valor_eleccion(*peso, heuristica.get(origen, destino), i_f);
+        if let Some(eleccion) = valores_eleccion.get_mut(origen, destino) {
+            *eleccion = valor;
         }
     }
 }
@@ -165,27 +462,38 @@ pub fn evaluacion_caminos(
     hormigas: &Hormigas,
     n_hormigas: usize,
     distancias: &Distancias,
-) -> EvaluacionCaminos {
+) -> Result<EvaluacionCaminos, Box<dyn Error>> {
     let mut evaluacion_caminos = Vec::with_capacity(n_hormigas);
-    for hormiga in hormigas.iter() {
-        let camino = &hormiga.camino;
-        let mut distancia_total = 0;
-        for i in 0..camino.len() - 1 {
-            let a = camino[i];
-            let b = camino[i + 1];
-            distancia_total += distancias[a as usize][b as usize];
+    for (k, hormiga) in hormigas.iter().enumerate() {
+        if hormiga.camino.is_empty() {
+            return Err(format!("La hormiga {} no tiene camino que evaluar", k + 1).into());
         }
-        let Some(inicio) = camino.first() else {
-            panic!("Error: Algo salio mal :p")
-        };
-        let Some(ultimo) = camino.last() else {
-            panic!("Error: Algo salio mal :p")
-        };
-        distancia_total += distancias[*ultimo as usize][*inicio as usize];
+        // Incluye el regreso del ultimo nodo al inicio
+        let distancia_total = aristas_camino(&hormiga.camino)
+            .map(|(a, b)| distancias[a as usize][b as usize])
+            .sum();
         evaluacion_caminos.push(distancia_total);
     }
 
-    evaluacion_caminos
+    Ok(evaluacion_caminos)
+}
+
+// Aristas de un camino cerrado, incluido el regreso al inicio
+fn aristas_camino(camino: &Camino) -> impl Iterator<Item = (Nodo, Nodo)> + '_ {
+    let cierre = camino.last().zip(camino.first());
+    camino
+        .windows(2)
+        .map(|ventana| (ventana[0], ventana[1]))
+        .chain(cierre.map(|(a, b)| (*a, *b)))
+}
+
+pub fn depositar_camino(camino: &Camino, aportacion: Peso, feromonas: &mut Feromonas) {
+    // Para cada arista de nuestro camino
+    for (origen, destino) in aristas_camino(camino) {
+        if let Some(peso) = feromonas.get_mut(origen, destino) {
+            *peso += aportacion;
+        }
+    }
 }
 
 pub fn actualizacion_feromona(
@@ -195,15 +503,91 @@ pub fn actualizacion_feromona(
 ) {
     for (index, hormiga) in hormigas.iter().enumerate() {
         // Aportacion segun la inversa de la evaluacion del camino
-        let aportacion: f64 = 1.0 / evaluacion_caminos[index] as f64;
-        let camino = &hormiga.camino;
-        // Para cada arista de nuestro camino
-        for ventana in camino.windows(2) {
-            let origen = ventana[0] as usize;
-            let destino = ventana[1];
-
-            if let Some(peso) = feromonas[origen].get_mut(&destino) {
-                *peso += aportacion;
+        let aportacion: f64 = 1.0 / evaluacion_caminos[index].max(1) as f64;
+        depositar_camino(&hormiga.camino, aportacion, feromonas);
+    }
+}
+
+pub fn actualizacion_feromona_estrategia(
+    configuracion: &Configuracion,
+    hormigas: &Hormigas,
+    feromonas: &mut Feromonas,
+    evaluacion_caminos: &EvaluacionCaminos,
+    mejor_camino: &Camino,
+    mejor_costo: u32,
+) {
+    // Hormigas de la iteracion ordenadas de mejor a peor
+    let mut orden: Vec<usize> = (0..hormigas.len()).collect();
+    orden.sort_by_key(|&k| evaluacion_caminos[k]);
+
+    match configuracion.estrategia {
+        EstrategiaActualizacion::TodasLasHormigas => {
+            actualizacion_feromona(hormigas, feromonas, evaluacion_caminos);
+        }
+        EstrategiaActualizacion::Elitista => {
+            actualizacion_feromona(hormigas, feromonas, evaluacion_caminos);
+            let aportacion = configuracion.peso_elitista / mejor_costo.max(1) as Peso;
+            depositar_camino(mejor_camino, aportacion, feromonas);
+        }
+        EstrategiaActualizacion::Rango => {
+            let w = configuracion.hormigas_rango.max(1);
+            for (r, &k) in orden.iter().take(w - 1).enumerate() {
+                // r empieza en 0: la mejor de la iteracion tiene rango 1
+                let aportacion = (w - r - 1) as Peso / evaluacion_caminos[k].max(1) as Peso;
+                depositar_camino(&hormigas[k].camino, aportacion, feromonas);
+            }
+            let aportacion = w as Peso / mejor_costo.max(1) as Peso;
+            depositar_camino(mejor_camino, aportacion, feromonas);
+        }
+        EstrategiaActualizacion::MejorIteracion => {
+            if let Some(&k) = orden.first() {
+                let aportacion = 1.0 / evaluacion_caminos[k].max(1) as Peso;
+                depositar_camino(&hormigas[k].camino, aportacion, feromonas);
+            }
+        }
+    }
+}
+
+pub fn actualizacion_global_acs(
+    mejor_camino: &Camino,
+    mejor_costo: u32,
+    feromonas: &mut Feromonas,
+    p: Rho,
+) {
+    // Solo las aristas del mejor camino (incluido el regreso al inicio):
+    // τ_ij = (1 - ρ) * τ_ij + ρ / L_mejor
+    let aportacion = 1.0 / mejor_costo.max(1) as Peso;
+    for (origen, destino) in aristas_camino(mejor_camino) {
+        if let Some(peso) = feromonas.get_mut(origen, destino) {
+            *peso = (1.0 - p) * *peso + p * aportacion;
+        }
+    }
+}
+
+pub fn actualizacion_feromona_mmas(
+    camino: &Camino,
+    costo: u32,
+    feromonas: &mut Feromonas,
+    limites: &LimitesFeromona,
+) {
+    // Solo deposita la hormiga elegida (mejor de la iteracion o global)
+    let aportacion = 1.0 / costo.max(1) as Peso;
+    for (origen, destino) in aristas_camino(camino) {
+        if let Some(peso) = feromonas.get_mut(origen, destino) {
+            *peso = (*peso + aportacion).min(limites.maximo);
+        }
+    }
+}
+
+pub fn limitar_feromona(
+    conjunto_aristas: &Base,
+    feromonas: &mut Feromonas,
+    limites: &LimitesFeromona,
+) {
+    for (origen, vecinos) in conjunto_aristas.iter().enumerate() {
+        for &vecino in vecinos.iter() {
+            if let Some(peso) = feromonas.get_mut(origen as Nodo, vecino) {
+                *peso = limites.acotar(*peso);
             }
         }
     }
@@ -211,75 +595,209 @@ pub fn actualizacion_feromona(
 
 pub fn evapozacion_feromona(conjunto_aristas: &Base, feromonas: &mut Feromonas, p: Rho) {
     for (origen, vecinos) in conjunto_aristas.iter().enumerate() {
-        for vecino in vecinos.iter() {
-            if let Some(peso) = feromonas[origen].get_mut(&(*vecino)) {
+        for &vecino in vecinos.iter() {
+            if let Some(peso) = feromonas.get_mut(origen as Nodo, vecino) {
                 *peso *= 1.0 - p;
             }
         }
     }
 }
 
-pub fn leer_matriz(path: &str) -> Result<MatrizFromFile, Box<dyn Error>> {
-    let file = File::open(path)?;
-    let reader = BufReader::new(file);
-
-    let mut dimension = 0;
-    let mut leyendo_matriz = false;
-    let mut valores = Vec::new();
+// Aristas existentes: las de EDGE_DATA_SECTION si el archivo las trae, o el
+// grafo completo en otro caso. En ambos casos se descartan los lazos y las
+// aristas cuyo peso es uno de los `centinelas` (p. ej. 9999 o 100000000).
+pub fn get_conjunto_aristas_from_distancia(file: &MatrizFromFile, centinelas: &[u32]) -> Base {
+    // En SOP tampoco existe (i, j) si j debe ir antes que i, salvo la arista
+    // ficticia de costo 0 del ultimo nodo al inicio que cierra el camino
+    let n = file.dimension as usize;
+    let cierre_sop = |i: usize, j: usize| file.es_sop() && i == n - 1 && j == 0;
+    let existe = |i: usize, j: usize| {
+        i != j
+            && !centinelas.contains(&file.matriz[i][j])
+            && (!file.precedencias[i].contains(&(j as Nodo)) || cierre_sop(i, j))
+    };
+    match &file.aristas {
+        Some(aristas) => aristas
+            .iter()
+            .enumerate()
+            .map(|(i, vecinos)| {
+                vecinos
+                    .iter()
+                    .copied()
+                    .filter(|&j| existe(i, j as usize))
+                    .collect()
+            })
+            .collect(),
+        None => (0..n)
+            .map(|i| {
+                (0..n)
+                    .filter(|&j| existe(i, j))
+                    .map(|j| j as Nodo)
+                    .collect()
+            })
+            .collect(),
+    }
+}
 
-    for linea in reader.lines() {
-        let linea = linea?;
+// Costo con el que se reemplazan las aristas inexistentes en la matriz, para
+// que la busqueda local y los metodos exactos nunca las elijan
+pub const DISTANCIA_PROHIBIDA: u32 = u32::MAX / 4;
 
-        if linea.starts_with("DIMENSION:") {
-            dimension = linea["DIMENSION:".len()..].trim().parse()?;
-        } else if linea.trim() == "EDGE_WEIGHT_SECTION" {
-            leyendo_matriz = true;
-        } else if leyendo_matriz {
-            if linea.trim() == "EOF" {
-                break;
+pub fn prohibir_aristas_inexistentes(conjunto_aristas: &Base, distancias: &mut Distancias) {
+    for (i, vecinos) in conjunto_aristas.iter().enumerate() {
+        let mut existe = vec![false; distancias[i].len()];
+        for &j in vecinos {
+            existe[j as usize] = true;
+        }
+        for (j, distancia) in distancias[i].iter_mut().enumerate() {
+            if i != j && !existe[j] {
+                *distancia = DISTANCIA_PROHIBIDA;
             }
-
-            let numeros: Distancia = linea
-                .split_whitespace()
-                .map(|s| s.parse::<u32>())
-                .collect::<Result<_, _>>().expect("");
-
-            valores.extend(numeros);
         }
     }
+}
 
-    if dimension == 0 {
-        return Err("No se encontro la dimension".into());
+// Un camino es solucion si visita todos los nodos y existe la arista de
+// regreso al inicio; las hormigas que llegan a un callejon sin salida se
+// descartan
+pub fn camino_completo(conjunto_aristas: &Base, camino: &Camino) -> bool {
+    match (camino.first(), camino.last()) {
+        (Some(&inicio), Some(&ultimo)) => {
+            camino.len() == conjunto_aristas.len()
+                && (camino.len() == 1 || conjunto_aristas[ultimo as usize].contains(&inicio))
+        }
+        _ => false,
     }
+}
 
-    if valores.len() != dimension * dimension {
+// Revisa que un camino leido de otra fuente sea una solucion de la
+// instancia: cada nodo exactamente una vez, todas sus aristas existentes y,
+// en SOP, cada nodo despues de sus predecesores
+pub fn validar_camino(
+    conjunto_aristas: &Base,
+    precedencias: &Precedencias,
+    camino: &Camino,
+) -> Result<(), String> {
+    let n = conjunto_aristas.len();
+    if camino.len() != n {
         return Err(format!(
-            "Se esperaban {} valores pero se encontraron {}",
-            dimension * dimension,
-            valores.len()
-        )
-        .into());
-    }
-    let matriz = valores
-        .chunks(dimension)
-        .map(|fila| fila.to_vec())
-        .collect();
-
-    let object = MatrizFromFile::new(matriz, dimension as u32);
+            "El camino tiene {} nodos y la instancia {}",
+            camino.len(),
+            n
+        ));
+    }
+    let mut visitados = vec![false; n];
+    for &nodo in camino {
+        let Some(visitado) = visitados.get_mut(nodo as usize) else {
+            return Err(format!("Nodo {} fuera de la instancia", nodo + 1));
+        };
+        if *visitado {
+            return Err(format!("El nodo {} aparece mas de una vez", nodo + 1));
+        }
+        *visitado = true;
+    }
+    let mut posicion = vec![0; n];
+    for (i, &nodo) in camino.iter().enumerate() {
+        posicion[nodo as usize] = i;
+    }
+    for &nodo in camino {
+        if let Some(&previo) = precedencias[nodo as usize]
+            .iter()
+            .find(|&&previo| posicion[previo as usize] > posicion[nodo as usize])
+        {
+            return Err(format!(
+                "El nodo {} debe ir antes que {}",
+                previo + 1,
+                nodo + 1
+            ));
+        }
+    }
+    for i in 0..n {
+        let (a, b) = (camino[i], camino[(i + 1) % n]);
+        if n > 1 && !conjunto_aristas[a as usize].contains(&b) {
+            return Err(format!("La arista ({}, {}) no existe", a + 1, b + 1));
+        }
+    }
+    Ok(())
+}
 
-    Ok(object)
+// Costo de un camino cerrado, incluido el regreso al inicio
+pub fn costo_camino_cerrado(camino: &Camino, distancias: &Distancias) -> u64 {
+    let n = camino.len();
+    (0..n)
+        .map(|i| distancias[camino[i] as usize][camino[(i + 1) % n] as usize] as u64)
+        .sum()
 }
 
-pub fn get_conjunto_aristas_from_distancia(file: &MatrizFromFile) -> Base {
-    let mut conjunto_aristas: Base = Vec::with_capacity(file.dimension as usize);
-    for i in 0..file.dimension {
-        let mut fila: Vec<Nodo> = Vec::with_capacity(file.dimension as usize);
-        for j in 0..file.dimension {
-            if i != j {
-                fila.push(j as Nodo);
-            }
-        }
-        conjunto_aristas.push(fila);
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const X: u32 = DISTANCIA_PROHIBIDA;
+
+    #[test]
+    fn vecino_mas_cercano_completo() {
+        let distancias = vec![vec![0, 1, 9], vec![9, 0, 2], vec![3, 9, 0]];
+        let conjunto_aristas = vec![vec![1, 2], vec![0, 2], vec![0, 1]];
+        assert_eq!(
+            costo_vecino_mas_cercano(&conjunto_aristas, &distancias, 0),
+            6
+        );
+    }
+
+    #[test]
+    fn vecino_mas_cercano_sin_desbordar() {
+        // 5 aristas de 1e9 suman mas que u32::MAX
+        let n = 5;
+        let distancias: Distancias = (0..n)
+            .map(|i| {
+                (0..n)
+                    .map(|j| if i == j { 0 } else { 1_000_000_000 })
+                    .collect()
+            })
+            .collect();
+        let conjunto_aristas: Base = (0..n)
+            .map(|i| (0..n).filter(|&j| j != i).map(|j| j as Nodo).collect())
+            .collect();
+        assert_eq!(
+            costo_vecino_mas_cercano(&conjunto_aristas, &distancias, 0),
+            u32::MAX
+        );
+    }
+
+    #[test]
+    fn vecino_mas_cercano_sin_salida_usa_el_promedio() {
+        // 0 -> 1 es la mas barata, pero desde 1 solo se vuelve a 0
+        let distancias = vec![vec![0, 1, 5], vec![3, 0, X], vec![4, 7, 0]];
+        let conjunto_aristas = vec![vec![1, 2], vec![0], vec![0, 1]];
+        // Promedio (1 + 5 + 3 + 4 + 7) / 5 = 4, por 3 nodos
+        assert_eq!(
+            costo_vecino_mas_cercano(&conjunto_aristas, &distancias, 0),
+            12
+        );
+
+        // Recorre 0 -> 1 -> 2 pero de 2 no hay regreso a 0
+        let distancias = vec![vec![0, 1, 5], vec![3, 0, 2], vec![X, 7, 0]];
+        let conjunto_aristas = vec![vec![1, 2], vec![0, 2], vec![1]];
+        // Promedio (1 + 5 + 3 + 2 + 7) / 5 = 3, por 3 nodos
+        assert_eq!(
+            costo_vecino_mas_cercano(&conjunto_aristas, &distancias, 0),
+            9
+        );
+    }
+
+    #[test]
+    fn evaluacion_con_regreso_y_sin_camino() {
+        let distancias = vec![vec![0, 1, 9], vec![9, 0, 2], vec![3, 9, 0]];
+        let mut hormigas = vec![Hormiga {
+            camino: vec![0, 1, 2],
+        }];
+        assert_eq!(
+            evaluacion_caminos(&hormigas, 1, &distancias).unwrap(),
+            vec![6]
+        );
+
+        hormigas.push(Hormiga { camino: Vec::new() });
+        assert!(evaluacion_caminos(&hormigas, 2, &distancias).is_err());
     }
-    conjunto_aristas
 }
@@ -0,0 +1,839 @@
+use crate::sistema_hormigas::{
+    Base, Camino, Distancia, Distancias, MatrizFromFile, Nodo, Precedencias,
+};
+use std::error::Error;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+
+// Lector de instancias TSPLIB (TSP y ATSP).
+//
+// Formatos de EDGE_WEIGHT_FORMAT soportados: FUNCTION, FULL_MATRIX,
+// UPPER_ROW, LOWER_ROW, UPPER_DIAG_ROW, LOWER_DIAG_ROW, UPPER_COL,
+// LOWER_COL, UPPER_DIAG_COL y LOWER_DIAG_COL.
+// Tipos de EDGE_WEIGHT_TYPE con coordenadas: EUC_2D, EUC_3D, CEIL_2D,
+// MAN_2D, MAN_3D, MAX_2D, MAX_3D, ATT y GEO, calculados como indica la
+// especificacion TSPLIB 95.
+
+// Optimos (o mejores conocidos) de TSPLIB, para instancias sin BEST_KNOWN
+const OPTIMOS_TSPLIB: &[(&str, u32)] = &[
+    // ATSP
+    ("br17", 39),
+    ("ftv33", 1286),
+    ("ftv35", 1473),
+    ("ftv38", 1530),
+    ("ftv44", 1613),
+    ("ftv47", 1776),
+    ("ftv55", 1608),
+    ("ftv64", 1839),
+    ("ftv70", 1950),
+    ("ftv170", 2755),
+    ("p43", 5620),
+    ("ry48p", 14422),
+    ("ft53", 6905),
+    ("ft70", 38673),
+    ("kro124p", 36230),
+    ("rbg323", 1326),
+    ("rbg358", 1163),
+    ("rbg403", 2465),
+    ("rbg443", 2720),
+    // TSP
+    ("burma14", 3323),
+    ("ulysses16", 6859),
+    ("gr17", 2085),
+    ("gr21", 2707),
+    ("ulysses22", 7013),
+    ("gr24", 1272),
+    ("fri26", 937),
+    ("bayg29", 1610),
+    ("bays29", 2020),
+    ("dantzig42", 699),
+    ("swiss42", 1273),
+    ("att48", 10628),
+    ("gr48", 5046),
+    ("hk48", 11461),
+    ("eil51", 426),
+    ("berlin52", 7542),
+    ("brazil58", 25395),
+    ("st70", 675),
+    ("eil76", 538),
+    ("pr76", 108159),
+    ("rat99", 1211),
+    ("kroA100", 21282),
+    ("kroB100", 22141),
+    ("kroC100", 20749),
+    ("kroD100", 21294),
+    ("kroE100", 22068),
+    ("rd100", 7910),
+    ("eil101", 629),
+    ("lin105", 14379),
+    ("pr107", 44303),
+    ("pr124", 59030),
+    ("ch130", 6110),
+    ("pr136", 96772),
+    ("pr144", 58537),
+    ("ch150", 6528),
+    ("kroA150", 26524),
+    ("pr152", 73682),
+    ("d198", 15780),
+    ("kroA200", 29368),
+    ("tsp225", 3916),
+    ("gil262", 2378),
+    ("a280", 2579),
+    ("lin318", 42029),
+    ("pcb442", 50778),
+];
+
+// Valor optimo conocido de una instancia TSPLIB a partir de su nombre
+pub fn valor_optimo_tsplib(nombre: &str) -> Option<u32> {
+    OPTIMOS_TSPLIB
+        .iter()
+        .find(|(instancia, _)| *instancia == nombre)
+        .map(|(_, valor)| *valor)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum TipoPeso {
+    Explicito,
+    Euclidiana2D,
+    Euclidiana3D,
+    Techo2D,
+    Manhattan2D,
+    Manhattan3D,
+    Maximo2D,
+    Maximo3D,
+    Att,
+    Geografica,
+}
+
+impl TipoPeso {
+    fn from_str(valor: &str) -> Result<TipoPeso, Box<dyn Error>> {
+        Ok(match valor {
+            "EXPLICIT" => TipoPeso::Explicito,
+            "EUC_2D" => TipoPeso::Euclidiana2D,
+            "EUC_3D" => TipoPeso::Euclidiana3D,
+            "CEIL_2D" => TipoPeso::Techo2D,
+            "MAN_2D" => TipoPeso::Manhattan2D,
+            "MAN_3D" => TipoPeso::Manhattan3D,
+            "MAX_2D" => TipoPeso::Maximo2D,
+            "MAX_3D" => TipoPeso::Maximo3D,
+            "ATT" => TipoPeso::Att,
+            "GEO" => TipoPeso::Geografica,
+            _ => return Err(format!("EDGE_WEIGHT_TYPE no soportado: {}", valor).into()),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum FormatoPeso {
+    Funcion,
+    MatrizCompleta,
+    FilaSuperior,
+    FilaInferior,
+    FilaSuperiorDiagonal,
+    FilaInferiorDiagonal,
+    ColumnaSuperior,
+    ColumnaInferior,
+    ColumnaSuperiorDiagonal,
+    ColumnaInferiorDiagonal,
+}
+
+impl FormatoPeso {
+    fn from_str(valor: &str) -> Result<FormatoPeso, Box<dyn Error>> {
+        Ok(match valor {
+            "FUNCTION" => FormatoPeso::Funcion,
+            "FULL_MATRIX" => FormatoPeso::MatrizCompleta,
+            "UPPER_ROW" => FormatoPeso::FilaSuperior,
+            "LOWER_ROW" => FormatoPeso::FilaInferior,
+            "UPPER_DIAG_ROW" => FormatoPeso::FilaSuperiorDiagonal,
+            "LOWER_DIAG_ROW" => FormatoPeso::FilaInferiorDiagonal,
+            "UPPER_COL" => FormatoPeso::ColumnaSuperior,
+            "LOWER_COL" => FormatoPeso::ColumnaInferior,
+            "UPPER_DIAG_COL" => FormatoPeso::ColumnaSuperiorDiagonal,
+            "LOWER_DIAG_COL" => FormatoPeso::ColumnaInferiorDiagonal,
+            _ => return Err(format!("EDGE_WEIGHT_FORMAT no soportado: {}", valor).into()),
+        })
+    }
+
+    // Celdas (i, j) en el orden en que aparecen en EDGE_WEIGHT_SECTION.
+    // En formatos triangulares cada valor se copia tambien a (j, i).
+    fn celdas(self, n: usize) -> Vec<(usize, usize)> {
+        let mut celdas = Vec::new();
+        match self {
+            FormatoPeso::Funcion => {}
+            FormatoPeso::MatrizCompleta => {
+                for i in 0..n {
+                    for j in 0..n {
+                        celdas.push((i, j));
+                    }
+                }
+            }
+            // Por simetria, recorrer la parte superior por columnas es lo
+            // mismo que recorrer la inferior por filas y viceversa
+            FormatoPeso::FilaSuperior | FormatoPeso::ColumnaInferior => {
+                for i in 0..n {
+                    for j in i + 1..n {
+                        celdas.push((i, j));
+                    }
+                }
+            }
+            FormatoPeso::FilaInferior | FormatoPeso::ColumnaSuperior => {
+                for i in 0..n {
+                    for j in 0..i {
+                        celdas.push((i, j));
+                    }
+                }
+            }
+            FormatoPeso::FilaSuperiorDiagonal | FormatoPeso::ColumnaInferiorDiagonal => {
+                for i in 0..n {
+                    for j in i..n {
+                        celdas.push((i, j));
+                    }
+                }
+            }
+            FormatoPeso::FilaInferiorDiagonal | FormatoPeso::ColumnaSuperiorDiagonal => {
+                for i in 0..n {
+                    for j in 0..=i {
+                        celdas.push((i, j));
+                    }
+                }
+            }
+        }
+        celdas
+    }
+}
+
+// Formato de EDGE_DATA_SECTION: las aristas que existen en grafos no completos
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum FormatoAristas {
+    // Pares "i j", terminados en -1
+    ListaAristas,
+    // "i j1 j2 ... -1" por nodo, terminados en -1
+    ListaAdyacencia,
+}
+
+impl FormatoAristas {
+    fn from_str(valor: &str) -> Result<FormatoAristas, Box<dyn Error>> {
+        Ok(match valor {
+            "EDGE_LIST" => FormatoAristas::ListaAristas,
+            "ADJ_LIST" => FormatoAristas::ListaAdyacencia,
+            _ => return Err(format!("EDGE_DATA_FORMAT no soportado: {}", valor).into()),
+        })
+    }
+
+    // Pares (i, j) desde 0 a partir de los valores de la seccion (desde 1)
+    fn pares(self, valores: &[i64]) -> Result<Vec<(usize, usize)>, Box<dyn Error>> {
+        let indice = |v: i64| -> Result<usize, Box<dyn Error>> {
+            if v < 1 {
+                return Err(format!("Nodo invalido en EDGE_DATA_SECTION: {}", v).into());
+            }
+            Ok(v as usize - 1)
+        };
+        let mut pares = Vec::new();
+        let mut valores = valores.iter().copied();
+        match self {
+            FormatoAristas::ListaAristas => {
+                while let Some(i) = valores.next().filter(|&v| v != -1) {
+                    let j = valores
+                        .next()
+                        .ok_or("Arista incompleta en EDGE_DATA_SECTION")?;
+                    pares.push((indice(i)?, indice(j)?));
+                }
+            }
+            FormatoAristas::ListaAdyacencia => {
+                while let Some(i) = valores.next().filter(|&v| v != -1) {
+                    for j in valores.by_ref().take_while(|&v| v != -1) {
+                        pares.push((indice(i)?, indice(j)?));
+                    }
+                }
+            }
+        }
+        Ok(pares)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Seccion {
+    Ninguna,
+    Pesos,
+    Coordenadas,
+    Aristas,
+    // DISPLAY_DATA_SECTION, FIXED_EDGES_SECTION, etc. se ignoran
+    Otra,
+}
+
+// nint de TSPLIB: redondeo al entero mas cercano
+fn nint(x: f64) -> u32 {
+    (x + 0.5) as u32
+}
+
+// Latitud/longitud en radianes a partir de la notacion DDD.MM de TSPLIB.
+// Se trunca la parte entera igual que las implementaciones de referencia
+// (los optimos publicados se calcularon asi).
+fn radianes_geo(x: f64) -> f64 {
+    // TSPLIB define PI con 6 decimales; usar el valor exacto cambia distancias
+    #[allow(clippy::approx_constant)]
+    const PI: f64 = 3.141592;
+    let grados = x.trunc();
+    let minutos = x - grados;
+    PI * (grados + 5.0 * minutos / 3.0) / 180.0
+}
+
+fn distancia_coordenadas(tipo: TipoPeso, a: &[f64], b: &[f64]) -> u32 {
+    let dx = a[0] - b[0];
+    let dy = a[1] - b[1];
+    let dz = if a.len() > 2 && b.len() > 2 {
+        a[2] - b[2]
+    } else {
+        0.0
+    };
+    match tipo {
+        TipoPeso::Euclidiana2D => nint((dx * dx + dy * dy).sqrt()),
+        TipoPeso::Euclidiana3D => nint((dx * dx + dy * dy + dz * dz).sqrt()),
+        TipoPeso::Techo2D => (dx * dx + dy * dy).sqrt().ceil() as u32,
+        TipoPeso::Manhattan2D => nint(dx.abs() + dy.abs()),
+        TipoPeso::Manhattan3D => nint(dx.abs() + dy.abs() + dz.abs()),
+        TipoPeso::Maximo2D => nint(dx.abs()).max(nint(dy.abs())),
+        TipoPeso::Maximo3D => nint(dx.abs()).max(nint(dy.abs())).max(nint(dz.abs())),
+        TipoPeso::Att => {
+            // Distancia pseudo-euclidiana de att48 y att532
+            let r = ((dx * dx + dy * dy) / 10.0).sqrt();
+            let t = nint(r);
+            if (t as f64) < r {
+                t + 1
+            } else {
+                t
+            }
+        }
+        TipoPeso::Geografica => {
+            const RRR: f64 = 6378.388;
+            let (latitud_a, longitud_a) = (radianes_geo(a[0]), radianes_geo(a[1]));
+            let (latitud_b, longitud_b) = (radianes_geo(b[0]), radianes_geo(b[1]));
+            let q1 = (longitud_a - longitud_b).cos();
+            let q2 = (latitud_a - latitud_b).cos();
+            let q3 = (latitud_a + latitud_b).cos();
+            (RRR * (0.5 * ((1.0 + q1) * q2 - (1.0 - q1) * q3)).acos() + 1.0) as u32
+        }
+        TipoPeso::Explicito => 0,
+    }
+}
+
+// Separa "CLAVE : valor" o "CLAVE: valor"
+fn clave_valor(linea: &str) -> Option<(&str, &str)> {
+    let (clave, valor) = linea.split_once(':')?;
+    Some((clave.trim(), valor.trim()))
+}
+
+pub fn leer_matriz(path: &str) -> Result<MatrizFromFile, Box<dyn Error>> {
+    let file = File::open(path)?;
+    let reader = BufReader::new(file);
+
+    let mut nombre = String::new();
+    let mut tipo = String::new();
+    let mut dimension: usize = 0;
+    let mut mejor_conocido: Option<u32> = None;
+    let mut tipo_peso: Option<TipoPeso> = None;
+    let mut formato_peso: Option<FormatoPeso> = None;
+    let mut formato_aristas: Option<FormatoAristas> = None;
+
+    let mut seccion = Seccion::Ninguna;
+    let mut pesos: Vec<f64> = Vec::new();
+    let mut coordenadas: Vec<Vec<f64>> = Vec::new();
+    let mut datos_aristas: Vec<i64> = Vec::new();
+
+    for linea in reader.lines() {
+        let linea = linea?;
+        let ltrim = linea.trim();
+
+        if ltrim.is_empty() {
+            continue;
+        }
+        if ltrim == "EOF" {
+            break;
+        }
+
+        // Inicio de seccion
+        let palabra = ltrim.trim_end_matches(':').trim();
+        if palabra.ends_with("_SECTION") {
+            seccion = match palabra {
+                "EDGE_WEIGHT_SECTION" => Seccion::Pesos,
+                "NODE_COORD_SECTION" => Seccion::Coordenadas,
+                "EDGE_DATA_SECTION" => Seccion::Aristas,
+                _ => Seccion::Otra,
+            };
+            continue;
+        }
+
+        // Linea de encabezado
+        if let Some((clave, valor)) = clave_valor(ltrim) {
+            if clave.chars().all(|c| c.is_ascii_uppercase() || c == '_') {
+                match clave {
+                    "NAME" => nombre = valor.to_string(),
+                    "TYPE" => tipo = valor.to_string(),
+                    "DIMENSION" => dimension = valor.parse()?,
+                    "BEST_KNOWN" => mejor_conocido = valor.parse().ok(),
+                    "EDGE_WEIGHT_TYPE" => tipo_peso = Some(TipoPeso::from_str(valor)?),
+                    "EDGE_WEIGHT_FORMAT" => formato_peso = Some(FormatoPeso::from_str(valor)?),
+                    "EDGE_DATA_FORMAT" => formato_aristas = Some(FormatoAristas::from_str(valor)?),
+                    _ => {}
+                }
+                seccion = Seccion::Ninguna;
+                continue;
+            }
+        }
+
+        match seccion {
+            Seccion::Pesos => {
+                for s in ltrim.split_whitespace() {
+                    pesos.push(
+                        s.parse::<f64>()
+                            .map_err(|_| format!("Peso invalido en EDGE_WEIGHT_SECTION: {}", s))?,
+                    );
+                }
+            }
+            Seccion::Coordenadas => {
+                // Formato: Id x y [z]
+                let fila: Vec<f64> = ltrim
+                    .split_whitespace()
+                    .skip(1)
+                    .map(|s| s.parse::<f64>())
+                    .collect::<Result<_, _>>()
+                    .map_err(|_| format!("Coordenada invalida: {}", ltrim))?;
+                if fila.len() < 2 {
+                    return Err(format!("Coordenada incompleta: {}", ltrim).into());
+                }
+                coordenadas.push(fila);
+            }
+            Seccion::Aristas => {
+                for s in ltrim.split_whitespace() {
+                    datos_aristas.push(
+                        s.parse::<i64>()
+                            .map_err(|_| format!("Valor invalido en EDGE_DATA_SECTION: {}", s))?,
+                    );
+                }
+            }
+            Seccion::Ninguna | Seccion::Otra => {}
+        }
+    }
+
+    if dimension == 0 {
+        return Err("No se encontro la dimension".into());
+    }
+
+    // Los archivos sin EDGE_WEIGHT_TYPE (como los ftv) traen la matriz completa
+    let tipo_peso = tipo_peso.unwrap_or(TipoPeso::Explicito);
+    let n = dimension;
+    let mut matriz: Distancias = vec![vec![0; n]; n];
+    let es_sop = tipo.starts_with("SOP");
+    let mut precedencias: Precedencias = vec![Vec::new(); n];
+
+    if tipo_peso == TipoPeso::Explicito {
+        let formato = formato_peso.unwrap_or(FormatoPeso::MatrizCompleta);
+        let celdas = formato.celdas(n);
+        // Las instancias SOP repiten la dimension al inicio de la seccion
+        if es_sop && pesos.len() == celdas.len() + 1 {
+            pesos.remove(0);
+        }
+        if pesos.len() != celdas.len() {
+            return Err(format!(
+                "Se esperaban {} valores pero se encontraron {}",
+                celdas.len(),
+                pesos.len()
+            )
+            .into());
+        }
+        let simetrica = formato != FormatoPeso::MatrizCompleta;
+        for (&(i, j), &peso) in celdas.iter().zip(pesos.iter()) {
+            // SOP: -1 en (i, j) indica que j debe visitarse antes que i
+            if es_sop && peso == -1.0 {
+                if i != j {
+                    precedencias[i].push(j as Nodo);
+                }
+                continue;
+            }
+            if peso < 0.0 {
+                return Err(format!("Peso negativo en ({}, {}): {}", i, j, peso).into());
+            }
+            let peso = peso.round() as u32;
+            matriz[i][j] = peso;
+            if simetrica {
+                matriz[j][i] = peso;
+            }
+        }
+    } else {
+        if coordenadas.len() != n {
+            return Err(format!(
+                "Se esperaban {} coordenadas pero se encontraron {}",
+                n,
+                coordenadas.len()
+            )
+            .into());
+        }
+        for i in 0..n {
+            let fila: Distancia = (0..n)
+                .map(|j| {
+                    if i == j {
+                        0
+                    } else {
+                        distancia_coordenadas(tipo_peso, &coordenadas[i], &coordenadas[j])
+                    }
+                })
+                .collect();
+            matriz[i] = fila;
+        }
+    }
+
+    // Grafo no completo: solo existen las aristas de EDGE_DATA_SECTION, en
+    // ambos sentidos salvo en instancias asimetricas
+    let aristas = match formato_aristas {
+        None => None,
+        Some(formato) => {
+            let dirigido = tipo.starts_with("ATSP") || tipo.starts_with("SOP");
+            let mut aristas: Base = vec![Vec::new(); n];
+            for (i, j) in formato.pares(&datos_aristas)? {
+                if i >= n || j >= n {
+                    return Err(
+                        format!("Arista ({}, {}) fuera de la instancia", i + 1, j + 1).into(),
+                    );
+                }
+                aristas[i].push(j as Nodo);
+                if !dirigido {
+                    aristas[j].push(i as Nodo);
+                }
+            }
+            for vecinos in aristas.iter_mut() {
+                vecinos.sort_unstable();
+                vecinos.dedup();
+            }
+            Some(aristas)
+        }
+    };
+
+    // Sin BEST_KNOWN en el encabezado se busca en la tabla de TSPLIB
+    let mejor_conocido = mejor_conocido.or_else(|| valor_optimo_tsplib(&nombre));
+
+    Ok(MatrizFromFile::new(
+        nombre,
+        tipo,
+        matriz,
+        n as u32,
+        mejor_conocido,
+        aristas,
+        precedencias,
+    ))
+}
+
+// Escribe un camino en formato TOUR de TSPLIB: nodos desde 1 en
+// TOUR_SECTION, terminados en -1
+pub fn escribir_tour(
+    path: &str,
+    nombre: &str,
+    camino: &Camino,
+    costo: u32,
+) -> Result<(), Box<dyn Error>> {
+    let mut archivo = BufWriter::new(File::create(path)?);
+    writeln!(archivo, "NAME : {}.tour", nombre)?;
+    writeln!(archivo, "COMMENT : Longitud {}", costo)?;
+    writeln!(archivo, "TYPE : TOUR")?;
+    writeln!(archivo, "DIMENSION : {}", camino.len())?;
+    writeln!(archivo, "TOUR_SECTION")?;
+    for &nodo in camino {
+        writeln!(archivo, "{}", nodo + 1)?;
+    }
+    writeln!(archivo, "-1")?;
+    writeln!(archivo, "EOF")?;
+    archivo.flush()?;
+    Ok(())
+}
+
+// Lee el primer camino de TOUR_SECTION de un archivo .tour (nodos desde 0)
+pub fn leer_tour(path: &str) -> Result<Camino, Box<dyn Error>> {
+    let reader = BufReader::new(File::open(path)?);
+    let mut en_seccion = false;
+    let mut camino: Camino = Vec::new();
+
+    for linea in reader.lines() {
+        let linea = linea?;
+        let ltrim = linea.trim();
+        if ltrim.is_empty() {
+            continue;
+        }
+        if ltrim == "EOF" {
+            break;
+        }
+        if !en_seccion {
+            en_seccion = ltrim.trim_end_matches(':').trim() == "TOUR_SECTION";
+            continue;
+        }
+        for s in ltrim.split_whitespace() {
+            let nodo: i64 = s
+                .parse()
+                .map_err(|_| format!("Nodo invalido en TOUR_SECTION: {}", s))?;
+            if nodo == -1 {
+                return Ok(camino);
+            }
+            if nodo < 1 {
+                return Err(format!("Nodo invalido en TOUR_SECTION: {}", nodo).into());
+            }
+            camino.push((nodo - 1) as Nodo);
+        }
+    }
+
+    if !en_seccion {
+        return Err("No se encontro TOUR_SECTION".into());
+    }
+    Ok(camino)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::held_karp::held_karp;
+    use crate::sistema_hormigas::costo_camino_cerrado;
+    use std::fs;
+
+    // Escribe una instancia en el directorio temporal y devuelve su ruta
+    fn archivo_temporal(nombre: &str, contenido: &str) -> String {
+        let ruta = std::env::temp_dir().join(format!("{}_{}", std::process::id(), nombre));
+        fs::write(&ruta, contenido).unwrap();
+        ruta.to_string_lossy().into_owned()
+    }
+
+    fn leer(nombre: &str, contenido: &str) -> MatrizFromFile {
+        let ruta = archivo_temporal(nombre, contenido);
+        let file = leer_matriz(&ruta);
+        fs::remove_file(&ruta).unwrap();
+        file.unwrap()
+    }
+
+    fn instancia_explicita(tipo: &str, formato: &str, n: usize, pesos: &str) -> String {
+        format!(
+            "NAME: prueba\nTYPE: {}\nDIMENSION: {}\nEDGE_WEIGHT_TYPE: EXPLICIT\n\
+             EDGE_WEIGHT_FORMAT: {}\nEDGE_WEIGHT_SECTION\n{}\nEOF\n",
+            tipo, n, formato, pesos
+        )
+    }
+
+    fn instancia_coordenadas(tipo_peso: &str, coordenadas: &str) -> String {
+        let n = coordenadas.lines().filter(|l| !l.trim().is_empty()).count();
+        format!(
+            "NAME: prueba\nTYPE: TSP\nDIMENSION: {}\nEDGE_WEIGHT_TYPE: {}\n\
+             NODE_COORD_SECTION\n{}\nEOF\n",
+            n, tipo_peso, coordenadas
+        )
+    }
+
+    // Camino desde 1, como en los archivos .opt.tour
+    fn camino(nodos: &[Nodo]) -> Camino {
+        nodos.iter().map(|&nodo| nodo - 1).collect()
+    }
+
+    #[test]
+    fn formatos_triangulares() {
+        let esperada: Distancias = vec![
+            vec![0, 1, 2, 3],
+            vec![1, 0, 4, 5],
+            vec![2, 4, 0, 6],
+            vec![3, 5, 6, 0],
+        ];
+        // Los valores pueden repartirse en cualquier numero de lineas
+        let casos = [
+            ("UPPER_ROW", "1 2 3\n4 5\n6"),
+            ("LOWER_ROW", "1\n2 4\n3 5 6"),
+            ("UPPER_DIAG_ROW", "0 1 2 3\n0 4 5\n0 6\n0"),
+            ("LOWER_DIAG_ROW", "0\n1 0\n2 4 0\n3 5 6 0"),
+            ("UPPER_COL", "1\n2 4\n3 5 6"),
+            ("LOWER_COL", "1 2 3\n4 5\n6"),
+            ("UPPER_DIAG_COL", "0\n1 0\n2 4 0\n3 5 6 0"),
+            ("LOWER_DIAG_COL", "0 1 2 3 0 4 5 0 6 0"),
+        ];
+        for (formato, pesos) in casos {
+            let file = leer(formato, &instancia_explicita("TSP", formato, 4, pesos));
+            assert_eq!(file.matriz, esperada, "{}", formato);
+            assert_eq!(file.dimension, 4);
+        }
+    }
+
+    #[test]
+    fn matriz_completa_asimetrica() {
+        let file = leer(
+            "FULL_MATRIX",
+            &instancia_explicita("ATSP", "FULL_MATRIX", 3, "9999 1 2\n3 9999 4\n5 6 9999"),
+        );
+        assert_eq!(
+            file.matriz,
+            vec![vec![9999, 1, 2], vec![3, 9999, 4], vec![5, 6, 9999]]
+        );
+    }
+
+    #[test]
+    fn formato_funcion_usa_coordenadas() {
+        let contenido = "NAME: prueba\nTYPE: TSP\nDIMENSION: 3\nEDGE_WEIGHT_TYPE: EUC_2D\n\
+                         EDGE_WEIGHT_FORMAT: FUNCTION\nNODE_COORD_SECTION\n\
+                         1 0 0\n2 3 4\n3 0 8\nEOF\n";
+        let file = leer("FUNCTION", contenido);
+        assert_eq!(
+            file.matriz,
+            vec![vec![0, 5, 8], vec![5, 0, 5], vec![8, 5, 0]]
+        );
+    }
+
+    #[test]
+    fn pesos_faltantes_son_un_error() {
+        let ruta = archivo_temporal(
+            "faltantes",
+            &instancia_explicita("TSP", "UPPER_ROW", 4, "1 2 3 4 5"),
+        );
+        assert!(leer_matriz(&ruta).is_err());
+        fs::remove_file(&ruta).unwrap();
+    }
+
+    #[test]
+    fn distancias_att_de_att48() {
+        let coordenadas = "\
+1 6734 1453
+2 2233 10
+3 5530 1424
+4 401 841
+5 3082 1644
+6 7608 4458
+7 7573 3716
+8 7265 1268
+9 6898 1885
+10 1112 2049
+11 5468 2606
+12 5989 2873
+13 4706 2674
+14 4612 2035
+15 6347 2683
+16 6107 669
+17 7611 5184
+18 7462 3590
+19 7732 4723
+20 5900 3561
+21 4483 3369
+22 6101 1110
+23 5199 2182
+24 1633 2809
+25 4307 2322
+26 675 1006
+27 7555 4819
+28 7541 3981
+29 3177 756
+30 7352 4506
+31 7545 2801
+32 3245 3305
+33 6426 3173
+34 4608 1198
+35 23 2216
+36 7248 3779
+37 7762 4595
+38 7392 2244
+39 3484 2829
+40 6271 2135
+41 4985 140
+42 1916 1569
+43 7280 4899
+44 7509 3239
+45 10 2676
+46 6807 2993
+47 5185 3258
+48 3023 1942";
+        let file = leer("att48", &instancia_coordenadas("ATT", coordenadas));
+        // att48.opt.tour de TSPLIB
+        let optimo = camino(&[
+            1, 8, 38, 31, 44, 18, 7, 28, 6, 37, 19, 27, 17, 43, 30, 36, 46, 33, 20, 47, 21, 32, 39,
+            48, 5, 42, 24, 10, 45, 35, 4, 26, 2, 29, 34, 41, 16, 22, 3, 23, 14, 25, 13, 11, 12, 15,
+            40, 9,
+        ]);
+        assert_eq!(costo_camino_cerrado(&optimo, &file.matriz), 10628);
+        // Pseudo-euclidiana redondeada hacia arriba: sqrt(22341250 / 10) ~ 1494.7
+        assert_eq!(file.matriz[0][1], 1495);
+    }
+
+    #[test]
+    fn distancias_geo_de_ulysses16() {
+        let coordenadas = "\
+1 38.24 20.42
+2 39.57 26.15
+3 40.56 25.32
+4 36.26 23.12
+5 33.48 10.54
+6 37.56 12.19
+7 38.42 13.11
+8 37.52 20.44
+9 41.23 9.10
+10 41.17 13.05
+11 36.08 -5.21
+12 38.47 15.13
+13 38.15 15.35
+14 37.51 15.17
+15 35.49 14.32
+16 39.36 19.56";
+        let file = leer("ulysses16", &instancia_coordenadas("GEO", coordenadas));
+        // ulysses16.opt.tour de TSPLIB
+        let optimo = camino(&[1, 14, 13, 12, 7, 6, 15, 5, 11, 9, 10, 16, 3, 2, 4, 8]);
+        assert_eq!(costo_camino_cerrado(&optimo, &file.matriz), 6859);
+        assert_eq!(held_karp(&file).unwrap().1, 6859);
+        assert_eq!(valor_optimo_tsplib("ulysses16"), Some(6859));
+    }
+
+    #[test]
+    fn distancias_ceil_2d() {
+        let coordenadas = "1 0 0\n2 3 4\n3 1 1\n4 0.5 0";
+        let techo = leer("CEIL_2D", &instancia_coordenadas("CEIL_2D", coordenadas));
+        let euclidiana = leer("EUC_2D", &instancia_coordenadas("EUC_2D", coordenadas));
+        // Distancias enteras no cambian; sqrt(2) y 0.5 suben a 2 y 1
+        assert_eq!(techo.matriz[0][1], 5);
+        assert_eq!(techo.matriz[0][2], 2);
+        assert_eq!(techo.matriz[0][3], 1);
+        assert_eq!(euclidiana.matriz[0][1], 5);
+        assert_eq!(euclidiana.matriz[0][2], 1);
+        assert_eq!(euclidiana.matriz[0][3], 1);
+        // sqrt(1.25) ~ 1.12
+        assert_eq!(techo.matriz[2][3], 2);
+        assert_eq!(euclidiana.matriz[2][3], 1);
+    }
+
+    #[test]
+    fn ida_y_vuelta_de_tour() {
+        let original: Camino = vec![3, 0, 4, 1, 2];
+        let ruta = std::env::temp_dir()
+            .join(format!("{}_prueba.tour", std::process::id()))
+            .to_string_lossy()
+            .into_owned();
+        escribir_tour(&ruta, "prueba", &original, 42).unwrap();
+        let leido = leer_tour(&ruta);
+        fs::remove_file(&ruta).unwrap();
+        assert_eq!(leido.unwrap(), original);
+    }
+
+    // Instancias del generador leidas con el mismo lector que las de TSPLIB
+    #[test]
+    fn lee_instancias_generadas() {
+        use generador_instancias::generador::{generar_instancia, Parametros, TipoInstancia};
+
+        let ruta = std::env::temp_dir()
+            .join(format!("{}_generada.atsp", std::process::id()))
+            .to_string_lossy()
+            .into_owned();
+        generar_instancia(&ruta, &Parametros::new(TipoInstancia::Atsp, 12, 1)).unwrap();
+        let file = leer_matriz(&ruta);
+        fs::remove_file(&ruta).unwrap();
+        let file = file.unwrap();
+        assert_eq!(file.tipo, "ATSP");
+        assert_eq!(file.dimension, 12);
+        assert_eq!(file.matriz.len(), 12);
+        assert!(file.matriz.iter().all(|fila| fila.len() == 12));
+        assert!((0..12).any(|i| (0..12).any(|j| file.matriz[i][j] != file.matriz[j][i])));
+
+        let ruta = ruta.replace(".atsp", ".tsp");
+        generar_instancia(&ruta, &Parametros::new(TipoInstancia::Tsp, 15, 1)).unwrap();
+        let file = leer_matriz(&ruta);
+        fs::remove_file(&ruta).unwrap();
+        let file = file.unwrap();
+        assert_eq!(file.tipo, "TSP");
+        assert_eq!(file.dimension, 15);
+        assert_eq!(file.matriz.len(), 15);
+        assert!((0..15).all(|i| (0..15).all(|j| file.matriz[i][j] == file.matriz[j][i])));
+    }
+}
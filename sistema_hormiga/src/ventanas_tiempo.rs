@@ -0,0 +1,396 @@
+use crate::sistema_hormigas::*;
+use std::error::Error;
+use std::fs;
+
+// TSP con ventanas de tiempo (TSPTW). Cada ciudad tiene una ventana
+// [inicio, fin] y un tiempo de servicio; el camino sale del deposito (nodo 0)
+// en el tiempo 0, si llega antes del inicio de una ventana espera y debe
+// empezar el servicio a mas tardar en el fin. El objetivo es la distancia
+// recorrida entre los caminos factibles.
+
+// Peso de cada unidad de tardanza en el deposito de feromona de caminos
+// infactibles, para que al principio tambien aprendan de ellos
+const PENALIZACION_TARDANZA: f64 = 10.0;
+
+#[derive(Debug, Clone, Copy)]
+pub struct Ventana {
+    pub inicio: u32,
+    pub fin: u32,
+    pub servicio: u32,
+}
+
+#[derive(Debug)]
+pub struct InstanciaVentanas {
+    pub nombre: String,
+    pub distancias: Distancias,
+    pub ventanas: Vec<Ventana>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EvaluacionVentanas {
+    // Distancia total, incluido el regreso al deposito
+    pub costo: u32,
+    // Tiempo de llegada de regreso al deposito
+    pub makespan: u32,
+    // Suma de los retrasos respecto al fin de cada ventana
+    pub tardanza: u32,
+    pub ciudades_tarde: usize,
+}
+
+impl EvaluacionVentanas {
+    pub fn es_factible(&self) -> bool {
+        self.tardanza == 0
+    }
+
+    // Primero los caminos sin tardanza y despues la menor distancia
+    fn clave(&self) -> (u32, u32) {
+        (self.tardanza, self.costo)
+    }
+}
+
+// Formato de Dumas / Gendreau: una linea de titulo ("!! nombre ..."), una de
+// encabezados y una fila por ciudad
+//   CUST NO.  XCOORD.  YCOORD.  DEMAND  READY TIME  DUE DATE  SERVICE TIME
+// terminadas con "999". La primera ciudad es el deposito. Los tiempos de
+// viaje son la distancia euclidiana truncada.
+pub fn leer_ventanas(path: &str) -> Result<InstanciaVentanas, Box<dyn Error>> {
+    let contenido = fs::read_to_string(path)?;
+    let mut nombre = std::path::Path::new(path)
+        .file_name()
+        .map_or(path.to_string(), |s| s.to_string_lossy().into_owned());
+    let mut coordenadas: Vec<(f64, f64)> = Vec::new();
+    let mut ventanas: Vec<Ventana> = Vec::new();
+
+    for linea in contenido.lines() {
+        let ltrim = linea.trim();
+        if ltrim.is_empty() {
+            continue;
+        }
+        if let Some(titulo) = ltrim.strip_prefix("!!") {
+            if let Some(palabra) = titulo.split_whitespace().next() {
+                nombre = palabra.to_string();
+            }
+            continue;
+        }
+        let Ok(valores) = ltrim
+            .split_whitespace()
+            .map(|s| s.parse::<f64>())
+            .collect::<Result<Vec<f64>, _>>()
+        else {
+            // Encabezado de columnas
+            continue;
+        };
+        if valores.len() == 1 && valores[0] == 999.0 {
+            break;
+        }
+        if valores.len() < 7 {
+            return Err(format!("Fila incompleta: {}", ltrim).into());
+        }
+        coordenadas.push((valores[1], valores[2]));
+        ventanas.push(Ventana {
+            inicio: valores[4] as u32,
+            fin: valores[5] as u32,
+            servicio: valores[6] as u32,
+        });
+    }
+
+    if coordenadas.is_empty() {
+        return Err("No se encontraron ciudades".into());
+    }
+    let distancias = coordenadas
+        .iter()
+        .map(|&(xi, yi)| {
+            coordenadas
+                .iter()
+                .map(|&(xj, yj)| ((xi - xj).powi(2) + (yi - yj).powi(2)).sqrt() as u32)
+                .collect()
+        })
+        .collect();
+
+    Ok(InstanciaVentanas {
+        nombre,
+        distancias,
+        ventanas,
+    })
+}
+
+// Tiempo en que termina el servicio en `destino` saliendo de `origen` en
+// `tiempo`, y el retraso respecto al fin de su ventana
+fn llegada(instancia: &InstanciaVentanas, origen: Nodo, destino: Nodo, tiempo: u32) -> (u32, u32) {
+    let ventana = instancia.ventanas[destino as usize];
+    let arribo = tiempo + instancia.distancias[origen as usize][destino as usize];
+    let inicio_servicio = arribo.max(ventana.inicio);
+    (
+        inicio_servicio + ventana.servicio,
+        inicio_servicio.saturating_sub(ventana.fin),
+    )
+}
+
+pub fn evaluar_ventanas(instancia: &InstanciaVentanas, camino: &Camino) -> EvaluacionVentanas {
+    let mut evaluacion = EvaluacionVentanas {
+        costo: 0,
+        makespan: 0,
+        tardanza: 0,
+        ciudades_tarde: 0,
+    };
+    let Some(&deposito) = camino.first() else {
+        return evaluacion;
+    };
+
+    let mut tiempo = instancia.ventanas[deposito as usize].servicio;
+    let regreso = camino.last().map(|&ultimo| (ultimo, deposito));
+    let aristas = camino
+        .windows(2)
+        .map(|arista| (arista[0], arista[1]))
+        .chain(regreso);
+    for (origen, destino) in aristas {
+        evaluacion.costo += instancia.distancias[origen as usize][destino as usize];
+        let (salida, retraso) = llegada(instancia, origen, destino, tiempo);
+        if retraso > 0 {
+            evaluacion.tardanza += retraso;
+            evaluacion.ciudades_tarde += 1;
+        }
+        tiempo = salida;
+    }
+    // Al deposito no se le da servicio al regresar
+    evaluacion.makespan = tiempo - instancia.ventanas[deposito as usize].servicio;
+    evaluacion
+}
+
+// Construccion desde el deposito: solo se consideran las ciudades que aun
+// se alcanzan dentro de su ventana. Si ninguna se alcanza, la hormiga elige
+// entre todas las no visitadas y el camino queda con tardanza.
+fn construccion_caminos_ventanas(
+    instancia: &InstanciaVentanas,
+    conjunto_aristas: &Base,
+    valores_eleccion: &ValoresEleccion,
+    hormigas: &mut Hormigas,
+) {
+    let n = conjunto_aristas.len();
+    for hormiga in hormigas.iter_mut() {
+        let mut visitados = Visitados::new(n);
+        let mut pesos = Vec::with_capacity(n);
+        let mut tiempo = instancia.ventanas[0].servicio;
+        visitados.insertar(0);
+
+        while hormiga.camino.len() < n {
+            let origen = *hormiga.camino.last().unwrap();
+            let no_visitados: Vec<Nodo> = conjunto_aristas[origen as usize]
+                .iter()
+                .copied()
+                .filter(|&j| !visitados.contiene(j))
+                .collect();
+            let a_tiempo: Vec<Nodo> = no_visitados
+                .iter()
+                .copied()
+                .filter(|&j| llegada(instancia, origen, j, tiempo).1 == 0)
+                .collect();
+            let factibles = if a_tiempo.is_empty() {
+                &no_visitados
+            } else {
+                &a_tiempo
+            };
+
+            let Some(siguiente) = seleccion_ruleta(origen, factibles, valores_eleccion, &mut pesos)
+            else {
+                break;
+            };
+            tiempo = llegada(instancia, origen, siguiente, tiempo).0;
+            hormiga.camino.push(siguiente);
+            visitados.insertar(siguiente);
+        }
+    }
+}
+
+pub fn sistema_hormigas_ventanas(
+    configuracion: &Configuracion,
+    instancia: &InstanciaVentanas,
+) -> Option<(Camino, EvaluacionVentanas)> {
+    let n = instancia.distancias.len();
+    let conjunto_aristas: Base = (0..n)
+        .map(|i| (0..n).filter(|&j| j != i).map(|j| j as Nodo).collect())
+        .collect();
+    let p: Rho = configuracion.rho;
+
+    let mut feromonas = algoritmo_inicializacion(&conjunto_aristas);
+    let heuristica = calcular_heuristica(
+        &conjunto_aristas,
+        &instancia.distancias,
+        configuracion.importancia_distancia,
+    );
+
+    // Valores de eleccion con la forma de `feromonas`, recalculados en su
+    // lugar al inicio de cada iteracion
+    let mut valores_eleccion = feromonas.clone();
+
+    let mut mejor: Option<(Camino, EvaluacionVentanas)> = None;
+
+    for i in 1..=configuracion.max_iteraciones {
+        print!("\rProgreso: {}/{}", i, configuracion.max_iteraciones);
+        std::io::Write::flush(&mut std::io::stdout()).unwrap();
+
+        actualizar_valores_eleccion(
+            &mut valores_eleccion,
+            &conjunto_aristas,
+            &feromonas,
+            &heuristica,
+            configuracion.importancia_feromona,
+        );
+
+        // Todas las hormigas salen del deposito
+        let mut hormigas = inicializacion_hormigas(configuracion.n_hormigas, 0);
+        construccion_caminos_ventanas(
+            instancia,
+            &conjunto_aristas,
+            &valores_eleccion,
+            &mut hormigas,
+        );
+        hormigas.retain(|hormiga| hormiga.camino.len() == n);
+
+        let evaluaciones: Vec<EvaluacionVentanas> = hormigas
+            .iter()
+            .map(|hormiga| evaluar_ventanas(instancia, &hormiga.camino))
+            .collect();
+
+        if let Some((k, evaluacion)) = evaluaciones
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, evaluacion)| evaluacion.clave())
+        {
+            if mejor
+                .as_ref()
+                .is_none_or(|(_, actual)| evaluacion.clave() < actual.clave())
+            {
+                mejor = Some((hormigas[k].camino.clone(), *evaluacion));
+            }
+        }
+
+        // Deposito 1 / (L_k + penalizacion * tardanza_k), incluido el arco
+        // de regreso al deposito, que tambien cuenta en L_k
+        evapozacion_feromona(&conjunto_aristas, &mut feromonas, p);
+        for (hormiga, evaluacion) in hormigas.iter().zip(&evaluaciones) {
+            let aportacion = 1.0
+                / (evaluacion.costo as Peso + PENALIZACION_TARDANZA * evaluacion.tardanza as Peso)
+                    .max(1.0);
+            depositar_camino(&hormiga.camino, aportacion, &mut feromonas);
+        }
+    }
+    println!();
+    mejor
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Deposito en (0, 0) con ventana [0, 30] y tres ciudades:
+    //   1: (3, 4) [10, 20]    2: (6, 8) [0, 12]    3: (6, 0) [0, 30], servicio 2
+    // d01 = 5, d02 = 10, d03 = 6, d12 = 5, d13 = 5, d23 = 8
+    const INSTANCIA: &str = "src/files/ventanas.txt";
+
+    fn evaluacion(
+        costo: u32,
+        makespan: u32,
+        tardanza: u32,
+        ciudades_tarde: usize,
+    ) -> EvaluacionVentanas {
+        EvaluacionVentanas {
+            costo,
+            makespan,
+            tardanza,
+            ciudades_tarde,
+        }
+    }
+
+    #[test]
+    fn lee_el_formato_de_dumas() {
+        let instancia = leer_ventanas(INSTANCIA).unwrap();
+        assert_eq!(instancia.nombre, "prueba4");
+        assert_eq!(instancia.ventanas.len(), 4);
+        assert_eq!(
+            instancia.distancias,
+            vec![
+                vec![0, 5, 10, 6],
+                vec![5, 0, 5, 5],
+                vec![10, 5, 0, 8],
+                vec![6, 5, 8, 0],
+            ]
+        );
+        let deposito = instancia.ventanas[0];
+        assert_eq!(
+            (deposito.inicio, deposito.fin, deposito.servicio),
+            (0, 30, 0)
+        );
+        let ultima = instancia.ventanas[3];
+        assert_eq!((ultima.inicio, ultima.fin, ultima.servicio), (0, 30, 2));
+    }
+
+    #[test]
+    fn evalua_espera_tardanza_y_regreso() {
+        let instancia = leer_ventanas(INSTANCIA).unwrap();
+
+        // Llega a 1 en 5 y espera a 10; llega a 2 en 15 (3 tarde); a 3 en
+        // 23 y sale en 25; regresa al deposito en 31 (1 tarde)
+        assert_eq!(
+            evaluar_ventanas(&instancia, &vec![0, 1, 2, 3]),
+            evaluacion(24, 31, 4, 2)
+        );
+        // 2 en 10, 1 en 15, 3 en 20 y sale en 22, deposito en 28
+        let factible = evaluar_ventanas(&instancia, &vec![0, 2, 1, 3]);
+        assert_eq!(factible, evaluacion(26, 28, 0, 0));
+        assert!(factible.es_factible());
+        // 3 en 6 y sale en 8, 1 en 13, 2 en 18 (6 tarde), deposito en 28
+        assert_eq!(
+            evaluar_ventanas(&instancia, &vec![0, 3, 1, 2]),
+            evaluacion(26, 28, 6, 1)
+        );
+    }
+
+    #[test]
+    fn la_construccion_prefiere_sucesores_a_tiempo() {
+        let instancia = leer_ventanas(INSTANCIA).unwrap();
+        let n = instancia.distancias.len();
+        let conjunto_aristas: Base = (0..n)
+            .map(|i| (0..n).filter(|&j| j != i).map(|j| j as Nodo).collect())
+            .collect();
+        let feromonas = algoritmo_inicializacion(&conjunto_aristas);
+        let heuristica = MatrizAristas::new(&conjunto_aristas, |_, _| 1.0);
+        let mut valores_eleccion = feromonas.clone();
+        actualizar_valores_eleccion(
+            &mut valores_eleccion,
+            &conjunto_aristas,
+            &feromonas,
+            &heuristica,
+            1.0,
+        );
+
+        fijar_semilla(5);
+        let mut hormigas = inicializacion_hormigas(50, 0);
+        construccion_caminos_ventanas(
+            &instancia,
+            &conjunto_aristas,
+            &valores_eleccion,
+            &mut hormigas,
+        );
+
+        for hormiga in &hormigas {
+            let camino = &hormiga.camino;
+            assert_eq!(camino.len(), n);
+            let mut tiempo = 0;
+            for (k, arista) in camino.windows(2).enumerate() {
+                let (origen, siguiente) = (arista[0], arista[1]);
+                let hay_a_tiempo = (0..n as Nodo)
+                    .filter(|j| !camino[..=k].contains(j))
+                    .any(|j| llegada(&instancia, origen, j, tiempo).1 == 0);
+                let (salida, retraso) = llegada(&instancia, origen, siguiente, tiempo);
+                assert!(!hay_a_tiempo || retraso == 0, "{:?}", camino);
+                tiempo = salida;
+            }
+            // Desde 1 (en el tiempo 10) la ciudad 2 ya llegaria tarde
+            if camino[1] == 1 {
+                assert_eq!(camino, &vec![0, 1, 3, 2]);
+            }
+        }
+        assert!(hormigas.iter().any(|hormiga| hormiga.camino[1] == 1));
+    }
+}